@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use advent2021::get_input::InputConfig;
+use advent2021::registry;
+
+fn solvers(c: &mut Criterion) {
+    let provider = InputConfig::from_env()
+        .and_then(|c| c.provider())
+        .expect("input config");
+    let registry = registry();
+    for day in 1..=25 {
+        let mut solvers = registry.day(day).peekable();
+        if solvers.peek().is_none() {
+            continue;
+        }
+        let input = match provider.input(day) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("skipping day {}: {}", day, e);
+                continue;
+            }
+        };
+        let mut group = c.benchmark_group(format!("day{}", day));
+        group.sample_size(10);
+        for solver in solvers {
+            group.bench_function(format!("part{}", solver.part()), |b| {
+                b.iter(|| solver.solve(&input).expect("solver failed"))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, solvers);
+criterion_main!(benches);
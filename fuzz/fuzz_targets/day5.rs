@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use advent2021::day5;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        // Huge coordinates are safe: maps over MAX_MAP_CELLS are an error.
+        let _ = day5::solve_part1(s);
+        let _ = day5::solve_part2(s);
+    }
+});
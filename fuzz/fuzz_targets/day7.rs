@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use advent2021::day7;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        // Huge positions are safe: part 2 returns Error::Overflow once
+        // they are more than 65535 apart, before searching between them.
+        let _ = day7::solve_part1(s);
+        let _ = day7::solve_part2(s);
+    }
+});
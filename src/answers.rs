@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::get_input::InputProvider;
+use crate::solver::{Answer, Registry};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum Expected {
+    Int(i64),
+    Text(String),
+}
+
+impl From<Expected> for Answer {
+    fn from(e: Expected) -> Self {
+        match e {
+            Expected::Int(n) => Answer::Int(n as i128),
+            Expected::Text(s) => match s.parse::<i128>() {
+                Ok(n) => Answer::Int(n),
+                Err(_) => Answer::Text(s),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part1: Option<Expected>,
+    part2: Option<Expected>,
+}
+
+/// Expected answers keyed by profile, then `dayN`, then `partN`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Manifest(BTreeMap<String, BTreeMap<String, DayAnswers>>);
+
+impl Manifest {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+    pub fn load(path: &Path) -> Result<Self> {
+        let s = fs::read_to_string(path)
+            .map_err(|e| anyhow!("could not read {}: {}", path.display(), e))?;
+        Self::parse(&s).map_err(|e| anyhow!("invalid manifest {}: {}", path.display(), e))
+    }
+    pub fn expected(&self, profile: &str, day: u8, part: u8) -> Option<Answer> {
+        let day = self.0.get(profile)?.get(&format!("day{}", day))?;
+        let e = match part {
+            1 => day.part1.as_ref(),
+            2 => day.part2.as_ref(),
+            _ => None,
+        }?;
+        Some(e.clone().into())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: Answer,
+    },
+    /// The manifest has no answer for this puzzle yet.
+    Unknown,
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'static str,
+    pub actual: Option<Answer>,
+    pub verdict: Verdict,
+}
+
+impl Check {
+    pub fn is_failure(&self) -> bool {
+        matches!(self.verdict, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+/// Runs every registered solver against its input and compares the result
+/// with the manifest entry for `profile`.
+pub fn verify(
+    registry: &Registry,
+    provider: &dyn InputProvider,
+    manifest: &Manifest,
+    profile: &str,
+) -> Vec<Check> {
+    let mut inputs = BTreeMap::new();
+    registry
+        .all()
+        .map(|solver| {
+            let input = inputs
+                .entry(solver.day())
+                .or_insert_with(|| provider.input(solver.day()));
+            let result = match input {
+                Ok(s) => solver.solve(s).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let expected = manifest.expected(profile, solver.day(), solver.part());
+            let (actual, verdict) = match (result, expected) {
+                (Err(e), _) => (None, Verdict::Error(e)),
+                (Ok(a), None) => (Some(a), Verdict::Unknown),
+                (Ok(a), Some(e)) if a == e => (Some(a), Verdict::Pass),
+                (Ok(a), Some(e)) => (Some(a), Verdict::Fail { expected: e }),
+            };
+            Check {
+                day: solver.day(),
+                part: solver.part(),
+                title: solver.title(),
+                actual,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::get_input::InputError;
+    use crate::solver::Puzzle;
+
+    const MANIFEST: &str = r#"
+[default]
+day1 = { part1 = 3, part2 = "18446744073709551616" }
+
+[sam]
+day1 = { part1 = 4 }
+"#;
+
+    static LEN: Puzzle<usize> = Puzzle::new(1, 1, "Len", |s| Ok(s.len()), |n| Ok(n.into()));
+    static BIG: Puzzle<()> = Puzzle::new(
+        1,
+        2,
+        "Big",
+        |_| Ok(()),
+        |_| Ok((u64::MAX as i128 + 1).into()),
+    );
+    static BROKEN: Puzzle<()> = Puzzle::new(
+        2,
+        1,
+        "Broken",
+        |_| Ok(()),
+        |_| Err(Error::invalid("no winner!")),
+    );
+
+    struct Fixed;
+
+    impl InputProvider for Fixed {
+        fn input(&self, _day: u8) -> Result<String, InputError> {
+            Ok("abc".to_string())
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut r = Registry::new();
+        r.register(&LEN);
+        r.register(&BIG);
+        r.register(&BROKEN);
+        r
+    }
+
+    #[test]
+    fn test_expected() -> Result<()> {
+        let m = Manifest::parse(MANIFEST)?;
+        assert_eq!(Some(Answer::Int(3)), m.expected("default", 1, 1));
+        assert_eq!(Some(Answer::Int(4)), m.expected("sam", 1, 1));
+        assert_eq!(None, m.expected("sam", 1, 2));
+        assert_eq!(None, m.expected("default", 2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        let m = Manifest::parse(MANIFEST)?;
+        let checks = verify(&registry(), &Fixed, &m, "default");
+        let verdicts: Vec<&Verdict> = checks.iter().map(|c| &c.verdict).collect();
+        assert_eq!(verdicts[0], &Verdict::Pass);
+        assert_eq!(verdicts[1], &Verdict::Pass);
+        assert!(matches!(verdicts[2], Verdict::Error(_)));
+        let checks = verify(&registry(), &Fixed, &m, "sam");
+        assert_eq!(
+            checks[0].verdict,
+            Verdict::Fail {
+                expected: Answer::Int(4)
+            }
+        );
+        assert_eq!(checks[1].verdict, Verdict::Unknown);
+        assert!(checks[0].is_failure() && !checks[1].is_failure());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shipped_manifest_parses() -> Result<()> {
+        let m = Manifest::load(Path::new(ANSWERS_FILE))?;
+        assert_eq!(Some(Answer::Int(1387)), m.expected(DEFAULT_PROFILE, 1, 1));
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::solver::{Solver, Timings};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub median_parse: Duration,
+    pub median_solve: Duration,
+}
+
+fn median(sorted: &[Duration]) -> Duration {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+impl Stats {
+    pub fn from_timings(timings: &[Timings]) -> Option<Self> {
+        if timings.is_empty() {
+            return None;
+        }
+        let sorted = |f: fn(&Timings) -> Duration| {
+            let mut v: Vec<Duration> = timings.iter().map(f).collect();
+            v.sort();
+            v
+        };
+        let total = sorted(Timings::total);
+        Some(Self {
+            runs: timings.len(),
+            min: total[0],
+            median: median(&total),
+            max: total[total.len() - 1],
+            median_parse: median(&sorted(|t| t.parse)),
+            median_solve: median(&sorted(|t| t.solve)),
+        })
+    }
+}
+
+/// Solves `input` `runs` times, failing on the first error.
+pub fn bench(solver: &dyn Solver, input: &str, runs: usize) -> Result<Stats> {
+    let timings = (0..runs)
+        .map(|_| solver.solve_timed(input).map(|(_, t)| t))
+        .collect::<Result<Vec<Timings>>>()?;
+    Stats::from_timings(&timings).ok_or_else(|| Error::invalid("at least one run is needed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn t(parse: u64, solve: u64) -> Timings {
+        Timings {
+            parse: ms(parse),
+            solve: ms(solve),
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let s = Stats::from_timings(&[t(1, 9), t(2, 3), t(1, 1), t(4, 4)]).unwrap();
+        assert_eq!(4, s.runs);
+        assert_eq!(ms(2), s.min);
+        assert_eq!(ms(6) + ms(1) / 2, s.median);
+        assert_eq!(ms(10), s.max);
+        assert_eq!(ms(1) + ms(1) / 2, s.median_parse);
+        assert_eq!(ms(3) + ms(1) / 2, s.median_solve);
+        assert_eq!(None, Stats::from_timings(&[]));
+    }
+}
@@ -1,28 +1,298 @@
-use std::num::ParseIntError;
+use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::parsers::{integer, unsigned};
+use crate::solver::{Puzzle, Registry};
+use crate::stream::{parse_str_lines, LineReader};
 
-use crate::get_input::get_input;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
-fn parse_input() -> Result<Vec<u64>, ParseIntError> {
-    get_input("day1.txt")
-        .unwrap()
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|n| n.parse::<u64>())
-        .collect::<Result<Vec<u64>, ParseIntError>>()
+pub(crate) fn parse_input(input: &str) -> Result<Vec<u64>> {
+    parse_str_lines(input, unsigned)
 }
 
-pub fn day1_1() -> Result<u64, ParseIntError> {
-    let v = parse_input()?;
-    Ok(v.windows(2)
-        .fold(0, |acc, c| if c[0] < c[1] { acc + 1 } else { acc }))
+/// What to do with lines of a sonar dump that are blank or not a depth.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Gaps {
+    /// Fail at the first malformed line, skipping blank ones.
+    #[default]
+    Fail,
+    /// Leave bad lines out.
+    Skip,
+    /// Replace bad lines with depths on a straight line between the good
+    /// readings either side.
+    Interpolate,
 }
 
-pub fn day1_2() -> Result<u64, ParseIntError> {
-    let v = parse_input()?;
-    Ok(v.windows(3)
-        .map(|w| w.iter().sum())
-        .into_iter()
-        .collect::<Vec<u64>>()
-        .windows(2)
-        .fold(0, |acc, w| if w[0] < w[1] { acc + 1 } else { acc }))
+/// A line of a sonar dump that was blank or not a depth.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BadReading {
+    /// Line number, counting from 1.
+    pub line: usize,
+    pub text: String,
+}
+
+/// Depths read from a possibly noisy sonar dump, with the lines that had
+/// to be skipped or filled in.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SonarReadings {
+    pub depths: Vec<i64>,
+    pub bad: Vec<BadReading>,
+}
+
+/// Reads one signed depth per line, surrounded by any whitespace, handling
+/// blank and malformed lines as `gaps` says. Blank lines at the end are
+/// ignored.
+pub fn parse_sonar(input: &str, gaps: Gaps) -> Result<SonarReadings> {
+    let lines: Vec<&str> = input.lines().collect();
+    let end = lines
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    let mut readings = Vec::with_capacity(end);
+    let mut bad = Vec::new();
+    for (i, &line) in lines[..end].iter().enumerate() {
+        let text = line.trim();
+        if let Ok(depth) = finish(text, integer::<i64>(text)) {
+            readings.push(Some(depth));
+            continue;
+        }
+        if gaps == Gaps::Fail {
+            if text.is_empty() {
+                continue;
+            }
+            return Err(Error::parse(input, text, "not a depth"));
+        }
+        bad.push(BadReading {
+            line: i + 1,
+            text: line.to_string(),
+        });
+        if gaps == Gaps::Interpolate {
+            readings.push(None);
+        }
+    }
+    Ok(SonarReadings {
+        depths: fill_gaps(&readings),
+        bad,
+    })
+}
+
+/// Fills each run of missing readings from the good readings either side,
+/// rounding towards the earlier one. Runs at the start or end copy their
+/// only neighbour.
+fn fill_gaps(readings: &[Option<i64>]) -> Vec<i64> {
+    let good: Vec<(usize, i64)> = readings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| d.map(|d| (i, d)))
+        .collect();
+    let (first, last) = match (good.first(), good.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Vec::new(),
+    };
+    let mut depths = vec![first.1; first.0];
+    for pair in good.windows(2) {
+        let ((i, a), (j, b)) = (pair[0], pair[1]);
+        let rise = i128::from(b) - i128::from(a);
+        depths.extend(
+            (i..j).map(|k| (i128::from(a) + rise * (k - i) as i128 / (j - i) as i128) as i64),
+        );
+    }
+    depths.resize(readings.len(), last.1);
+    depths
+}
+
+/// Counts how often the sum of `window` consecutive depths is larger than
+/// the sum of the window before it, in one pass holding `window` depths.
+///
+/// Neighbouring windows share all but their first and last depths, so this
+/// compares each depth with the one `window` places before it, which also
+/// keeps the sums from overflowing. A window of 0 never increases.
+pub fn count_increases<T: PartialOrd>(depths: impl IntoIterator<Item = T>, window: usize) -> u64 {
+    let mut recent = VecDeque::with_capacity(window);
+    let mut count = 0;
+    for depth in depths.into_iter().filter(|_| window > 0) {
+        if recent.len() == window && recent.pop_front().is_some_and(|d| d < depth) {
+            count += 1;
+        }
+        recent.push_back(depth);
+    }
+    count
+}
+
+/// [`count_increases`] over depths read one line at a time.
+pub fn count_increases_reader(input: impl BufRead, window: usize) -> Result<u64> {
+    let mut error = None;
+    let depths = LineReader::new(input)
+        .parse_lines(unsigned::<u64>)
+        .map_while(|d| d.map_err(|e| error = Some(e)).ok());
+    let count = count_increases(depths, window);
+    error.map_or(Ok(count), Err)
+}
+
+fn part1(v: &[u64]) -> u64 {
+    count_increases(v.iter().copied(), 1)
+}
+
+fn part2(v: &[u64]) -> u64 {
+    count_increases(v.iter().copied(), 3)
+}
+
+/// Solves part 1 reading depths one line at a time.
+pub fn solve_part1_reader(input: impl BufRead) -> Result<u64> {
+    count_increases_reader(input, 1)
+}
+
+/// Solves part 2 reading depths one line at a time.
+pub fn solve_part2_reader(input: impl BufRead) -> Result<u64> {
+    count_increases_reader(input, 3)
+}
+
+pub fn solve_part1(input: &str) -> Result<u64> {
+    Ok(part1(&parse_input(input)?))
+}
+
+pub fn solve_part2(input: &str) -> Result<u64> {
+    Ok(part2(&parse_input(input)?))
+}
+
+pub fn day1_1() -> Result<u64> {
+    solve_part1(&read_day(1)?)
+}
+
+pub fn day1_2() -> Result<u64> {
+    solve_part2(&read_day(1)?)
+}
+
+static PART1: Puzzle<Vec<u64>> =
+    Puzzle::new(1, 1, "Sonar Sweep", parse_input, |v| Ok(part1(&v).into()))
+        .with_stream(|r| Ok(solve_part1_reader(r)?.into()));
+static PART2: Puzzle<Vec<u64>> =
+    Puzzle::new(1, 2, "Sonar Sweep", parse_input, |v| Ok(part2(&v).into()))
+        .with_stream(|r| Ok(solve_part2_reader(r)?.into()));
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn test_solve_from_str() -> Result<()> {
+        assert_eq!(7, solve_part1(EXAMPLE)?);
+        assert_eq!(5, solve_part2(EXAMPLE)?);
+        Ok(())
+    }
+
+    fn increases_by_sums(v: &[u64], window: usize) -> u64 {
+        let sums: Vec<u64> = v.windows(window).map(|w| w.iter().sum()).collect();
+        sums.windows(2).filter(|p| p[0] < p[1]).count() as u64
+    }
+
+    #[test]
+    fn test_count_increases_matches_window_sums() -> Result<()> {
+        let depths = parse_input(EXAMPLE)?;
+        for window in 1..=depths.len() + 1 {
+            assert_eq!(
+                increases_by_sums(&depths, window),
+                count_increases(depths.iter().copied(), window),
+                "window {}",
+                window
+            );
+            assert_eq!(
+                count_increases(depths.iter().copied(), window),
+                count_increases_reader(EXAMPLE.as_bytes(), window)?
+            );
+        }
+        assert_eq!(0, count_increases(depths, 0));
+        assert_eq!(0, count_increases(vec![u64::MAX, 1, u64::MAX], 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_from_reader() -> Result<()> {
+        assert_eq!(7, solve_part1_reader(EXAMPLE.as_bytes())?);
+        assert_eq!(
+            5,
+            solve_part2_reader(EXAMPLE.replace('\n', "\r\n").as_bytes())?
+        );
+        let e = solve_part2_reader("199\n200\n2o8\n".as_bytes()).unwrap_err();
+        assert!(
+            e.to_string().starts_with("parse error at line 3, column 2"),
+            "{}",
+            e
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sonar() -> Result<()> {
+        let noisy = " 10\n-5\n\nabc\r\n 13 \n12x\n\n\n";
+        let e = parse_sonar(noisy, Gaps::Fail).unwrap_err();
+        assert!(
+            e.to_string()
+                .starts_with("parse error at line 4, column 1: not a depth"),
+            "{}",
+            e
+        );
+        let gappy = parse_sonar("1\n\n2\r\n", Gaps::Fail)?;
+        assert_eq!((vec![1, 2], vec![]), (gappy.depths, gappy.bad));
+        let e = parse_sonar("1\n  abc\n", Gaps::Fail).unwrap_err();
+        assert!(
+            e.to_string()
+                .starts_with("parse error at line 2, column 3: not a depth"),
+            "{}",
+            e
+        );
+        let skipped = parse_sonar(noisy, Gaps::Skip)?;
+        assert_eq!(vec![10, -5, 13], skipped.depths);
+        let lines: Vec<usize> = skipped.bad.iter().map(|b| b.line).collect();
+        assert_eq!(vec![3, 4, 6], lines);
+        assert_eq!("12x", skipped.bad[2].text);
+        let filled = parse_sonar(noisy, Gaps::Interpolate)?;
+        assert_eq!(vec![10, -5, 1, 7, 13, 13], filled.depths);
+        assert_eq!(skipped.bad, filled.bad);
+        assert_eq!(3, count_increases(filled.depths, 1));
+        assert_eq!(
+            vec![4, 4, 4],
+            parse_sonar("?\n4\n?\n", Gaps::Interpolate)?.depths
+        );
+        assert!(parse_sonar("?\n", Gaps::Interpolate)?.depths.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_names_line() {
+        let e = solve_part1("199\n200\n2o8\n").unwrap_err();
+        assert!(
+            e.to_string().starts_with("parse error at line 3, column 2"),
+            "{}",
+            e
+        );
+    }
+
+    #[test]
+    fn test_stream_accepts_same_input() {
+        for input in ["1\n \n3\r\n\n2\n4\n\n", "1\n+5\n", "1\n 5\n", "1\n-5\n"] {
+            let read = |s: Result<u64>| s.map_err(|e| e.to_string());
+            assert_eq!(
+                read(solve_part1(input)),
+                read(solve_part1_reader(input.as_bytes())),
+                "{:?}",
+                input
+            );
+            assert_eq!(
+                read(solve_part2(input)),
+                read(solve_part2_reader(input.as_bytes())),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(2, solve_part1("1\n \n3\r\n\n2\n4\n\n").unwrap());
+    }
 }
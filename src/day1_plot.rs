@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use crate::day1::{parse_sonar, Gaps};
+use crate::error::Result;
+
+/// A sonar depth series ready to draw, deeper readings lower down. Readings
+/// deeper than the one before, the ones day 1 part 1 counts, are marked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepthPlot {
+    depths: Vec<i64>,
+    increased: Vec<bool>,
+    overlay: Option<Overlay>,
+}
+
+/// Sums of `window` depths, divided by `window` so they share the depths'
+/// scale. `means[i]` is the window ending at reading `i + window - 1`.
+#[derive(Clone, Debug, PartialEq)]
+struct Overlay {
+    window: usize,
+    means: Vec<f64>,
+}
+
+impl DepthPlot {
+    /// A plot of `depths`, with the sums of each `window` readings drawn over
+    /// them if `window` is given.
+    pub fn new(depths: Vec<i64>, window: Option<usize>) -> Self {
+        let increased = (0..depths.len())
+            .map(|i| i > 0 && depths[i - 1] < depths[i])
+            .collect();
+        let overlay = window.filter(|&w| w > 0).map(|window| Overlay {
+            window,
+            means: depths
+                .windows(window)
+                .map(|w| w.iter().map(|&d| i128::from(d)).sum::<i128>() as f64 / window as f64)
+                .collect(),
+        });
+        Self {
+            depths,
+            increased,
+            overlay,
+        }
+    }
+
+    /// A plot of the depths in a day 1 input, with bad lines handled as
+    /// `gaps` says.
+    pub fn from_input(input: &str, gaps: Gaps, window: Option<usize>) -> Result<Self> {
+        Ok(Self::new(parse_sonar(input, gaps)?.depths, window))
+    }
+
+    /// Whether each reading is deeper than the one before it.
+    pub fn increased(&self) -> &[bool] {
+        &self.increased
+    }
+
+    fn range(&self) -> (f64, f64) {
+        let min = self.depths.iter().min().copied().unwrap_or(0) as f64;
+        let max = self.depths.iter().max().copied().unwrap_or(0) as f64;
+        (min, max)
+    }
+
+    /// A chart `columns` wide with `rows` lines for the depths. When there
+    /// are more readings than columns each column shows the mean of its
+    /// readings, and is marked `+` if most of them increased; other readings
+    /// are `.`, and the window overlay is `o`.
+    pub fn ascii(&self, columns: usize, rows: usize) -> String {
+        let mut out = String::new();
+        if self.depths.is_empty() || columns == 0 || rows == 0 {
+            return out;
+        }
+        let columns = columns.min(self.depths.len());
+        let (min, max) = self.range();
+        let row = |depth: f64| {
+            if max > min {
+                ((depth - min) / (max - min) * (rows - 1) as f64).round() as usize
+            } else {
+                0
+            }
+        };
+        let mut chart = vec![vec![' '; columns]; rows];
+        let bucket =
+            |c: usize| c * self.depths.len() / columns..(c + 1) * self.depths.len() / columns;
+        for (c, column) in (0..columns).map(|c| (c, bucket(c))) {
+            let mean = |values: &mut dyn Iterator<Item = f64>| {
+                let (sum, n) = values.fold((0.0, 0), |(s, n), v| (s + v, n + 1));
+                (n > 0).then(|| sum / n as f64)
+            };
+            if let Some(o) = &self.overlay {
+                let from = column.start.max(o.window - 1);
+                let values = &mut (from..column.end).map(|i| o.means[i + 1 - o.window]);
+                if let Some(m) = mean(values) {
+                    chart[row(m)][c] = 'o';
+                }
+            }
+            let ups = self.increased[column.clone()]
+                .iter()
+                .filter(|&&b| b)
+                .count();
+            let values = &mut self.depths[column.clone()].iter().map(|&d| d as f64);
+            if let Some(m) = mean(values) {
+                chart[row(m)][c] = if 2 * ups > column.len() { '+' } else { '.' };
+            }
+        }
+        let label = |r: usize| {
+            if r == 0 {
+                min.to_string()
+            } else if r == rows - 1 {
+                max.to_string()
+            } else {
+                String::new()
+            }
+        };
+        let width = label(0).len().max(label(rows - 1).len());
+        for (r, line) in chart.iter().enumerate() {
+            let line: String = line.iter().collect();
+            let _ = writeln!(out, "{:>w$} |{}", label(r), line.trim_end(), w = width);
+        }
+        let _ = writeln!(out, "{:>w$} +{}", "", "-".repeat(columns), w = width);
+        let last = self.depths.len().to_string();
+        let _ = writeln!(
+            out,
+            "{:>w$}  1{:>pad$}",
+            "",
+            last,
+            w = width,
+            pad = columns.saturating_sub(1).max(last.len())
+        );
+        out
+    }
+
+    /// An SVG image `width` by `height` pixels: the depths as a line with
+    /// increases as red dots, and the window overlay as a dashed line.
+    pub fn svg(&self, width: u32, height: u32) -> String {
+        const MARGIN: f64 = 40.0;
+        let (w, h) = (f64::from(width), f64::from(height));
+        let (min, max) = self.range();
+        let x = |i: usize| {
+            let span = self.depths.len().saturating_sub(1).max(1) as f64;
+            MARGIN + i as f64 / span * (w - 2.0 * MARGIN)
+        };
+        let y = |depth: f64| {
+            let span = if max > min { max - min } else { 1.0 };
+            MARGIN + (depth - min) / span * (h - 2.0 * MARGIN)
+        };
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        );
+        let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            out,
+            r#"<text x="4" y="{:.1}" font-size="12">{}</text><text x="4" y="{:.1}" font-size="12">{}</text>"#,
+            y(min) + 4.0,
+            min,
+            y(max) + 4.0,
+            max
+        );
+        let points = |coords: &mut dyn Iterator<Item = (usize, f64)>| {
+            coords
+                .map(|(i, d)| format!("{:.1},{:.1}", x(i), y(d)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let depths = &mut self.depths.iter().enumerate().map(|(i, &d)| (i, d as f64));
+        let _ = writeln!(
+            out,
+            r#"<polyline fill="none" stroke="steelblue" stroke-width="1" points="{}"/>"#,
+            points(depths)
+        );
+        if let Some(o) = &self.overlay {
+            let means = &mut o
+                .means
+                .iter()
+                .enumerate()
+                .map(|(i, &m)| (i + o.window - 1, m));
+            let _ = writeln!(
+                out,
+                r#"<polyline fill="none" stroke="darkorange" stroke-width="1" stroke-dasharray="4 2" points="{}"/>"#,
+                points(means)
+            );
+        }
+        for (i, &d) in self.depths.iter().enumerate() {
+            if self.increased[i] {
+                let _ = writeln!(
+                    out,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="1.5" fill="crimson"/>"#,
+                    x(i),
+                    y(d as f64)
+                );
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1::solve_part1;
+
+    const EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn test_increases_match_part1() -> Result<()> {
+        let plot = DepthPlot::from_input(EXAMPLE, Gaps::Fail, Some(3))?;
+        let marked = plot.increased().iter().filter(|&&b| b).count() as u64;
+        assert_eq!(solve_part1(EXAMPLE)?, marked);
+        let svg = plot.svg(400, 200);
+        assert_eq!(marked as usize, svg.matches("<circle").count());
+        assert_eq!(2, svg.matches("<polyline").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ascii() -> Result<()> {
+        let chart = DepthPlot::from_input(EXAMPLE, Gaps::Fail, None)?.ascii(80, 5);
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(7, lines.len());
+        assert_eq!("199 |.+  .+", lines[0]);
+        assert_eq!("269 |       + +", lines[4]);
+        assert_eq!("     1       10", lines[6]);
+        assert_eq!(7, chart.matches('+').count() - 1);
+        let squeezed = DepthPlot::from_input(EXAMPLE, Gaps::Fail, Some(3))?.ascii(5, 5);
+        assert_eq!(
+            "199 |.o.\n    | +\n    |   o\n    |   +o\n269 |    .\n",
+            &squeezed[..squeezed.find("    +").unwrap()]
+        );
+        assert_eq!("", DepthPlot::new(Vec::new(), None).ascii(10, 10));
+        let signed = DepthPlot::new(vec![-100, 5], None).ascii(2, 2);
+        assert_eq!(
+            "-100 |.\n   5 | +\n",
+            &signed[..signed.find("     +").unwrap()]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use serde::Serialize;
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::day1::{count_increases, parse_sonar, Gaps};
+use crate::error::Result;
+
+/// Consecutive readings, each deeper than the one before.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Run {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// A reading shallower than the one before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct DepthDrop {
+    pub index: usize,
+    pub from: i64,
+    pub to: i64,
+}
+
+impl DepthDrop {
+    pub fn size(&self) -> u64 {
+        self.from.abs_diff(self.to)
+    }
+}
+
+/// Statistics for the `window` readings from `start`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct WindowStats {
+    pub start: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: i64,
+    pub max: i64,
+}
+
+/// A reading far from the mean of the window before it. `deviations` is
+/// infinite when every reading in that window was the same.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Anomaly {
+    pub index: usize,
+    pub depth: i64,
+    pub mean: f64,
+    pub deviations: f64,
+}
+
+/// A summary of a sonar depth series beyond the puzzle's count of
+/// increases. Indices count readings from 0.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DepthProfile {
+    pub readings: usize,
+    pub increases: u64,
+    pub longest_increasing_run: Option<Run>,
+    pub largest_drop: Option<DepthDrop>,
+    pub window: usize,
+    pub sigmas: f64,
+    pub rolling: Vec<WindowStats>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl DepthProfile {
+    /// Profiles `depths` using rolling windows of `window` readings, and
+    /// flags readings more than `sigmas` standard deviations from the mean
+    /// of the window before them.
+    pub fn new(depths: &[i64], window: usize, sigmas: f64) -> Self {
+        let rolling = rolling(depths, window);
+        let anomalies = anomalies(depths, window, &rolling, sigmas);
+        Self {
+            readings: depths.len(),
+            increases: count_increases(depths.iter().copied(), 1),
+            longest_increasing_run: longest_increasing_run(depths),
+            largest_drop: largest_drop(depths),
+            window,
+            sigmas,
+            rolling,
+            anomalies,
+        }
+    }
+}
+
+/// Profiles the depths in a day 1 input, failing at any malformed line.
+pub fn profile(input: &str, window: usize, sigmas: f64) -> Result<DepthProfile> {
+    let readings = parse_sonar(input, Gaps::Fail)?;
+    Ok(DepthProfile::new(&readings.depths, window, sigmas))
+}
+
+fn longest_increasing_run(depths: &[i64]) -> Option<Run> {
+    let mut best: Option<Run> = None;
+    let mut start = 0;
+    for i in 0..depths.len() {
+        if i > 0 && depths[i] <= depths[i - 1] {
+            start = i;
+        }
+        let run = Run {
+            start,
+            len: i + 1 - start,
+        };
+        if best.is_none_or(|b| run.len > b.len) {
+            best = Some(run);
+        }
+    }
+    best
+}
+
+fn largest_drop(depths: &[i64]) -> Option<DepthDrop> {
+    depths
+        .windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[1] < w[0])
+        .map(|(i, w)| DepthDrop {
+            index: i + 1,
+            from: w[0],
+            to: w[1],
+        })
+        .fold(None, |best: Option<DepthDrop>, r| {
+            if best.is_none_or(|b| r.size() > b.size()) {
+                Some(r)
+            } else {
+                best
+            }
+        })
+}
+
+/// Stats for every full window, in one pass. Sums are kept relative to the
+/// first reading to limit rounding in the variance.
+fn rolling(depths: &[i64], window: usize) -> Vec<WindowStats> {
+    let mut stats = Vec::with_capacity((depths.len() + 1).saturating_sub(window));
+    let base = match depths.first() {
+        Some(&d) if window > 0 => d as f64,
+        _ => return stats,
+    };
+    let (mut sum, mut offsets, mut squares) = (0i128, 0f64, 0f64);
+    let mut mins: VecDeque<usize> = VecDeque::new();
+    let mut maxes: VecDeque<usize> = VecDeque::new();
+    for (i, &d) in depths.iter().enumerate() {
+        let offset = d as f64 - base;
+        sum += i128::from(d);
+        offsets += offset;
+        squares += offset * offset;
+        while mins.back().is_some_and(|&j| depths[j] >= d) {
+            mins.pop_back();
+        }
+        mins.push_back(i);
+        while maxes.back().is_some_and(|&j| depths[j] <= d) {
+            maxes.pop_back();
+        }
+        maxes.push_back(i);
+        if i + 1 < window {
+            continue;
+        }
+        if i >= window {
+            let gone = depths[i - window];
+            let offset = gone as f64 - base;
+            sum -= i128::from(gone);
+            offsets -= offset;
+            squares -= offset * offset;
+        }
+        let start = i + 1 - window;
+        while mins.front().is_some_and(|&j| j < start) {
+            mins.pop_front();
+        }
+        while maxes.front().is_some_and(|&j| j < start) {
+            maxes.pop_front();
+        }
+        let n = window as f64;
+        let variance = squares / n - (offsets / n) * (offsets / n);
+        stats.push(WindowStats {
+            start,
+            mean: sum as f64 / n,
+            std_dev: variance.max(0.0).sqrt(),
+            min: depths[mins[0]],
+            max: depths[maxes[0]],
+        });
+    }
+    stats
+}
+
+/// Readings compared against the stats of the window ending just before
+/// them.
+fn anomalies(depths: &[i64], window: usize, rolling: &[WindowStats], sigmas: f64) -> Vec<Anomaly> {
+    rolling
+        .iter()
+        .zip(depths.iter().enumerate().skip(window))
+        .filter_map(|(w, (index, &depth))| {
+            let distance = (depth as f64 - w.mean).abs();
+            let deviations = if w.std_dev > 0.0 {
+                distance / w.std_dev
+            } else if distance > 0.0 {
+                f64::INFINITY
+            } else {
+                0.0
+            };
+            (deviations > sigmas).then_some(Anomaly {
+                index,
+                depth,
+                mean: w.mean,
+                deviations,
+            })
+        })
+        .collect()
+}
+
+impl fmt::Display for DepthProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "readings: {}", self.readings)?;
+        writeln!(f, "increases: {}", self.increases)?;
+        if let Some(r) = self.longest_increasing_run {
+            writeln!(
+                f,
+                "longest increasing run: {} readings from reading {}",
+                r.len,
+                r.start + 1
+            )?;
+        }
+        match self.largest_drop {
+            Some(r) => writeln!(
+                f,
+                "largest drop: {} at reading {} ({} -> {})",
+                r.size(),
+                r.index + 1,
+                r.from,
+                r.to
+            )?,
+            None => writeln!(f, "largest drop: none")?,
+        }
+        let means = self.rolling.iter().map(|w| w.mean);
+        if let (Some(lo), Some(hi)) = (means.clone().reduce(f64::min), means.reduce(f64::max)) {
+            writeln!(
+                f,
+                "rolling mean over {} readings: {:.1} to {:.1}",
+                self.window, lo, hi
+            )?;
+        }
+        writeln!(
+            f,
+            "anomalies beyond {} standard deviations: {}",
+            self.sigmas,
+            self.anomalies.len()
+        )?;
+        for a in self.anomalies.iter() {
+            writeln!(
+                f,
+                "  reading {}: {} vs mean {:.1} ({:.1} sd)",
+                a.index + 1,
+                a.depth,
+                a.mean,
+                a.deviations
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn test_runs_and_drops() -> Result<()> {
+        let p = profile(EXAMPLE, 3, 2.0)?;
+        assert_eq!(10, p.readings);
+        assert_eq!(7, p.increases);
+        assert_eq!(Some(Run { start: 0, len: 4 }), p.longest_increasing_run);
+        let drop = p.largest_drop.unwrap();
+        assert_eq!((4, 10), (drop.index, drop.size()));
+        let flat = DepthProfile::new(&[5, 5], 1, 1.0);
+        assert_eq!(Some(Run { start: 0, len: 1 }), flat.longest_increasing_run);
+        assert_eq!(None, flat.largest_drop);
+        assert_eq!(None, DepthProfile::new(&[], 3, 1.0).longest_increasing_run);
+        let signed = profile("-3\n-1\n-4\n2\n", 2, 1.0)?;
+        assert_eq!(
+            Some(Run { start: 0, len: 2 }),
+            signed.longest_increasing_run
+        );
+        assert_eq!(3, signed.largest_drop.unwrap().size());
+        assert_eq!(2, profile("1\n\n2\n", 2, 1.0)?.readings);
+        assert!(profile("1\nx\n2\n", 2, 1.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling() {
+        let depths = [3, 1, 4, 1, 5, 9, 2, 6];
+        let p = DepthProfile::new(&depths, 3, 3.0);
+        assert_eq!(6, p.rolling.len());
+        for w in p.rolling.iter() {
+            let slice = &depths[w.start..w.start + 3];
+            let mean = slice.iter().sum::<i64>() as f64 / 3.0;
+            let var = slice
+                .iter()
+                .map(|&d| (d as f64 - mean).powi(2))
+                .sum::<f64>()
+                / 3.0;
+            assert!((w.mean - mean).abs() < 1e-9);
+            assert!((w.std_dev - var.sqrt()).abs() < 1e-9);
+            assert_eq!(slice.iter().min(), Some(&w.min));
+            assert_eq!(slice.iter().max(), Some(&w.max));
+        }
+        assert!(DepthProfile::new(&depths, 9, 3.0).rolling.is_empty());
+        assert!(DepthProfile::new(&depths, 0, 3.0).rolling.is_empty());
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let depths = [100, 102, 98, 101, 99, 150, 100, 100, 100, 100];
+        let p = DepthProfile::new(&depths, 4, 3.0);
+        let flagged: Vec<usize> = p.anomalies.iter().map(|a| a.index).collect();
+        assert_eq!(vec![5], flagged);
+        let a = p.anomalies[0];
+        assert_eq!(150, a.depth);
+        assert!((a.mean - 100.0).abs() < 1e-9);
+        let flat = DepthProfile::new(&[7, 7, 7, 8], 3, 3.0);
+        assert_eq!(1, flat.anomalies.len());
+        assert!(flat.anomalies[0].deviations.is_infinite());
+    }
+}
@@ -1,127 +1,189 @@
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, digit1, line_ending},
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
+    branch::alt, bytes::complete::tag, character::complete::char, combinator::map_res,
+    sequence::separated_pair, IResult,
 };
 
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
+use crate::error::{finish, Error, Result};
+use crate::geometry::{Point2, Vec2};
+use crate::get_input::read_day;
+use crate::parsers::{lines, unsigned, with_trailing_newlines};
+use crate::solver::{Puzzle, Registry};
+use std::convert::TryFrom;
 
-#[derive(Eq, Debug, PartialEq)]
-struct Pos {
-    vertical: u32,
-    horizontal: u32,
-}
-
-#[derive(Eq, Debug, PartialEq)]
-enum Dir {
+#[derive(Clone, Copy, Eq, Debug, PartialEq)]
+pub enum Dir {
     Forward,
     Up,
     Down,
 }
 
-impl From<&str> for Dir {
-    fn from(i: &str) -> Self {
+impl Dir {
+    /// The direction moved in, with depth increasing downwards.
+    pub fn unit(&self) -> Vec2 {
+        match self {
+            Self::Forward => Vec2::new(1, 0),
+            Self::Up => Vec2::new(0, -1),
+            Self::Down => Vec2::new(0, 1),
+        }
+    }
+}
+
+impl TryFrom<&str> for Dir {
+    type Error = ();
+    fn try_from(i: &str) -> Result<Self, Self::Error> {
         match i {
-            "forward" => Self::Forward,
-            "up" => Self::Up,
-            "down" => Self::Down,
-            _ => panic!("bad dir"),
+            "forward" => Ok(Self::Forward),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => Err(()),
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Msg {
-    dir: Dir,
-    n: u32,
+/// A command such as `forward 5`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Msg {
+    pub dir: Dir,
+    pub n: u32,
 }
 
 impl Msg {
     fn parse_msg(i: &str) -> IResult<&str, Msg> {
-        let dir_parser = alt((tag("forward"), tag("down"), tag("up")));
-        let mut p = separated_pair(dir_parser, char(' '), digit1);
-        let (rem, (d, n)) = p(i)?;
-        Ok((
-            rem,
-            Msg {
-                dir: Dir::from(d),
-                n: n.parse::<u32>().unwrap(),
-            },
-        ))
+        let dir_parser = map_res(alt((tag("forward"), tag("down"), tag("up"))), Dir::try_from);
+        let mut p = separated_pair(dir_parser, char(' '), unsigned);
+        let (rem, (dir, n)) = p(i)?;
+        Ok((rem, Msg { dir, n }))
+    }
+    /// `n` steps in `dir`, or `None` if that overflows.
+    pub fn offset(&self) -> Option<Vec2> {
+        self.dir.unit().checked_mul(i64::from(self.n))
     }
 }
 
-fn step(pos: Pos, msg: &Msg) -> Pos {
-    match msg.dir {
-        Dir::Forward => Pos {
-            horizontal: pos.horizontal + msg.n,
-            ..pos
-        },
-        Dir::Down => Pos {
-            vertical: pos.vertical + msg.n,
-            ..pos
-        },
-        Dir::Up => Pos {
-            vertical: pos.vertical - msg.n,
-            ..pos
-        },
+/// One way of reading the submarine's commands.
+pub trait MovementModel {
+    type State;
+    fn start(&self) -> Self::State;
+    /// The state after `msg`, or `None` if it overflows.
+    fn apply(&self, state: Self::State, msg: &Msg) -> Option<Self::State>;
+    /// The puzzle answer for the state after the last command.
+    fn score(&self, state: Self::State) -> Result<i64>;
+}
+
+/// Part 1: every command moves the submarine directly.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Direct;
+
+impl MovementModel for Direct {
+    type State = Point2;
+    fn start(&self) -> Point2 {
+        Point2::ORIGIN
+    }
+    fn apply(&self, pos: Point2, msg: &Msg) -> Option<Point2> {
+        pos.checked_add(msg.offset()?)
+    }
+    fn score(&self, end: Point2) -> Result<i64> {
+        product(end)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Day2Pos {
+    pub pos: Point2,
+    pub aim: i64,
+}
+
+/// Part 2: `up` and `down` change the aim, and `forward` also dives by the
+/// aim for each step.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Aimed;
+
+impl MovementModel for Aimed {
+    type State = Day2Pos;
+    fn start(&self) -> Day2Pos {
+        Day2Pos {
+            pos: Point2::ORIGIN,
+            aim: 0,
+        }
+    }
+    fn apply(&self, pos: Day2Pos, msg: &Msg) -> Option<Day2Pos> {
+        let offset = msg.offset()?;
+        Some(match msg.dir {
+            Dir::Forward => Day2Pos {
+                pos: pos
+                    .pos
+                    .checked_add(Vec2::new(offset.x, pos.aim.checked_mul(offset.x)?))?,
+                ..pos
+            },
+            Dir::Up | Dir::Down => Day2Pos {
+                aim: pos.aim.checked_add(offset.y)?,
+                ..pos
+            },
+        })
+    }
+    fn score(&self, end: Day2Pos) -> Result<i64> {
+        product(end.pos)
     }
 }
 
 fn parse_msgs(s: &str) -> IResult<&str, Vec<Msg>> {
-    separated_list1(line_ending, Msg::parse_msg)(s)
+    with_trailing_newlines(lines(Msg::parse_msg))(s)
 }
 
-pub fn day2_1() -> Result<u32> {
-    let s = get_input("day2.txt")?;
-    let msgs = parse_msgs(&s).map_err(|_| anyhow!("parser error"))?.1;
-    let start = Pos {
-        horizontal: 0,
-        vertical: 0,
-    };
-    let end = msgs.iter().fold(start, step);
-    Ok(end.horizontal * end.vertical)
+fn read_msgs(input: &str) -> Result<Vec<Msg>> {
+    finish(input, parse_msgs(input))
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Day2Pos {
-    horizontal: u32,
-    vertical: u32,
-    aim: u32,
-}
-
-fn day2_step(pos: Day2Pos, msg: &Msg) -> Day2Pos {
-    match msg.dir {
-        Dir::Forward => Day2Pos {
-            horizontal: pos.horizontal + msg.n,
-            vertical: pos.vertical + (pos.aim * msg.n),
-            ..pos
-        },
-        Dir::Up => Day2Pos {
-            aim: pos.aim - msg.n,
-            ..pos
-        },
-        Dir::Down => Day2Pos {
-            aim: pos.aim + msg.n,
-            ..pos
-        },
-    }
-}
-
-pub fn day2_2() -> Result<u32> {
-    let s = get_input("day2.txt")?;
-    let msgs = parse_msgs(&s).map_err(|_| anyhow!("parser error"))?.1;
-    let start = Day2Pos {
-        horizontal: 0,
-        vertical: 0,
-        aim: 0,
-    };
-    let end = msgs.iter().fold(start, day2_step);
-    Ok(end.horizontal * end.vertical)
+fn product(end: Point2) -> Result<i64> {
+    end.x
+        .checked_mul(end.y)
+        .ok_or(Error::Overflow("final position"))
+}
+
+/// Runs `msgs` through `model` from its starting state.
+pub fn navigate<M: MovementModel>(model: &M, msgs: &[Msg]) -> Result<i64> {
+    let end = msgs
+        .iter()
+        .try_fold(model.start(), |state, msg| model.apply(state, msg))
+        .ok_or(Error::Overflow("position"))?;
+    model.score(end)
+}
+
+/// Parses the commands in `input` and runs them through `model`.
+pub fn solve_with<M: MovementModel>(model: &M, input: &str) -> Result<i64> {
+    navigate(model, &read_msgs(input)?)
+}
+
+fn part1(msgs: &[Msg]) -> Result<i64> {
+    navigate(&Direct, msgs)
+}
+
+fn part2(msgs: &[Msg]) -> Result<i64> {
+    navigate(&Aimed, msgs)
+}
+
+pub fn solve_part1(input: &str) -> Result<i64> {
+    solve_with(&Direct, input)
+}
+
+pub fn solve_part2(input: &str) -> Result<i64> {
+    solve_with(&Aimed, input)
+}
+
+pub fn day2_1() -> Result<i64> {
+    solve_part1(&read_day(2)?)
+}
+
+pub fn day2_2() -> Result<i64> {
+    solve_part2(&read_day(2)?)
+}
+
+static PART1: Puzzle<Vec<Msg>> = Puzzle::new(2, 1, "Dive!", read_msgs, |m| Ok(part1(&m)?.into()));
+static PART2: Puzzle<Vec<Msg>> = Puzzle::new(2, 2, "Dive!", read_msgs, |m| Ok(part2(&m)?.into()));
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
@@ -145,16 +207,8 @@ mod tests {
             dir: Dir::Forward,
             n: 2,
         };
-        let start = Pos {
-            vertical: 0,
-            horizontal: 0,
-        };
-        let expected = Pos {
-            vertical: 0,
-            horizontal: 2,
-        };
-        let actual = step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Direct.apply(Point2::new(0, 0), &msg);
+        assert_eq!(actual, Some(Point2::new(2, 0)));
     }
 
     #[test]
@@ -163,30 +217,21 @@ mod tests {
             dir: Dir::Down,
             n: 2,
         };
-        let start = Pos {
-            vertical: 0,
-            horizontal: 0,
-        };
-        let expected = Pos {
-            vertical: 2,
-            horizontal: 0,
-        };
-        let actual = step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Direct.apply(Point2::new(0, 0), &msg);
+        assert_eq!(actual, Some(Point2::new(0, 2)));
     }
     #[test]
     fn test_step_up() {
         let msg = Msg { dir: Dir::Up, n: 2 };
-        let start = Pos {
-            vertical: 2,
-            horizontal: 0,
-        };
-        let expected = Pos {
-            vertical: 0,
-            horizontal: 0,
-        };
-        let actual = step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Direct.apply(Point2::new(0, 2), &msg);
+        assert_eq!(actual, Some(Point2::new(0, 0)));
+    }
+
+    #[test]
+    fn test_step_up_past_surface() {
+        let msg = Msg { dir: Dir::Up, n: 3 };
+        let actual = Direct.apply(Point2::new(0, 2), &msg);
+        assert_eq!(actual, Some(Point2::new(0, -1)));
     }
 
     #[test]
@@ -196,34 +241,30 @@ mod tests {
             n: 2,
         };
         let start = Day2Pos {
-            vertical: 0,
-            horizontal: 0,
+            pos: Point2::new(0, 0),
             aim: 2,
         };
         let expected = Day2Pos {
-            vertical: 4,
-            horizontal: 2,
+            pos: Point2::new(2, 4),
             aim: 2,
         };
-        let actual = day2_step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Aimed.apply(start, &msg);
+        assert_eq!(actual, Some(expected));
     }
 
     #[test]
     fn test_day2_step_up() {
         let msg = Msg { dir: Dir::Up, n: 2 };
         let start = Day2Pos {
-            vertical: 0,
-            horizontal: 0,
+            pos: Point2::new(0, 0),
             aim: 2,
         };
         let expected = Day2Pos {
-            horizontal: 0,
-            vertical: 0,
+            pos: Point2::new(0, 0),
             aim: 0,
         };
-        let actual = day2_step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Aimed.apply(start, &msg);
+        assert_eq!(actual, Some(expected));
     }
 
     #[test]
@@ -233,16 +274,47 @@ mod tests {
             n: 2,
         };
         let start = Day2Pos {
-            vertical: 0,
-            horizontal: 0,
+            pos: Point2::new(0, 0),
             aim: 0,
         };
         let expected = Day2Pos {
-            vertical: 0,
-            horizontal: 0,
+            pos: Point2::new(0, 0),
             aim: 2,
         };
-        let actual = day2_step(start, &msg);
-        assert_eq!(actual, expected);
+        let actual = Aimed.apply(start, &msg);
+        assert_eq!(actual, Some(expected));
+    }
+
+    /// Moves the same way as part 1 but scores the distance travelled.
+    struct Distance;
+
+    impl MovementModel for Distance {
+        type State = (Point2, i64);
+        fn start(&self) -> Self::State {
+            (Point2::ORIGIN, 0)
+        }
+        fn apply(&self, (pos, travelled): Self::State, msg: &Msg) -> Option<Self::State> {
+            let pos = Direct.apply(pos, msg)?;
+            Some((pos, travelled.checked_add(i64::from(msg.n))?))
+        }
+        fn score(&self, (_, travelled): Self::State) -> Result<i64> {
+            Ok(travelled)
+        }
+    }
+
+    #[test]
+    fn test_custom_model() -> Result<()> {
+        let input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        assert_eq!(31, solve_with(&Distance, input)?);
+        assert_eq!(150, solve_with(&Direct, input)?);
+        assert_eq!(900, solve_with(&Aimed, input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_depth() -> Result<()> {
+        assert_eq!(-15, solve_part1("forward 5\nup 3\n")?);
+        assert_eq!(-75, solve_part2("up 3\nforward 5\n")?);
+        Ok(())
     }
 }
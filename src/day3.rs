@@ -1,11 +1,8 @@
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
-use nom::{
-    character::complete::{digit1, line_ending},
-    combinator::map_res,
-    multi::separated_list1,
-    IResult,
-};
+use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::parsers::{lines, with_trailing_newlines};
+use crate::solver::{Puzzle, Registry};
+use nom::{character::complete::digit1, combinator::map_res, IResult};
 use std::convert::TryFrom;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -15,10 +12,10 @@ enum Bit {
 }
 
 impl Bit {
-    fn as_number(&self, m: u32) -> u32 {
+    fn as_number(&self) -> u32 {
         match self {
             Self::Zero => 0,
-            Self::One => m,
+            Self::One => 1,
         }
     }
 }
@@ -80,21 +77,24 @@ impl Reading {
         &self.0
     }
     fn at(&self, ix: usize) -> Result<&Bit> {
-        self.get_reading().get(ix).ok_or(anyhow!("bad index"))
+        self.get_reading()
+            .get(ix)
+            .ok_or_else(|| Error::invalid(format!("reading has no bit {}", ix)))
     }
-    fn parse(s: &str) -> Result<Reading> {
+    fn parse(s: &str) -> Result<Reading, ()> {
         s.chars()
-            .map(|c| (Bit::try_from(c).map_err(|_| anyhow!("digit parsing error"))))
-            .collect::<Result<Vec<Bit>>>()
+            .map(Bit::try_from)
+            .collect::<Result<Vec<Bit>, ()>>()
             .map(Self)
     }
 
-    fn as_number(&self) -> u32 {
+    fn as_number(&self) -> Result<u32> {
         self.get_reading()
             .iter()
-            .rev()
-            .fold((0, 1), |acc, v| (acc.0 + v.as_number(acc.1), acc.1 * 2))
-            .0
+            .try_fold(0u32, |acc, v| {
+                acc.checked_mul(2)?.checked_add(v.as_number())
+            })
+            .ok_or(Error::Overflow("reading"))
     }
     fn matches_bit_at(&self, bit: &Bit, pos: usize) -> Result<bool> {
         let b = self.at(pos)?;
@@ -115,7 +115,7 @@ fn parse_reading(s: &str) -> IResult<&str, Reading> {
 }
 
 fn parse_readings(s: &str) -> IResult<&str, Vec<Reading>> {
-    separated_list1(line_ending, parse_reading)(s)
+    with_trailing_newlines(lines(parse_reading))(s)
 }
 
 fn _to_ds(readings: &[Reading]) -> Result<Vec<Count>> {
@@ -123,9 +123,8 @@ fn _to_ds(readings: &[Reading]) -> Result<Vec<Count>> {
         .iter()
         .map(|v| v.len())
         .max()
-        .ok_or(anyhow!("len error"))?;
+        .ok_or_else(|| Error::invalid("no readings"))?;
     (0..l)
-        .into_iter()
         .map(|ix| digits_at(readings, ix))
         .collect::<Result<Vec<Count>>>()
 }
@@ -133,25 +132,25 @@ fn _to_ds(readings: &[Reading]) -> Result<Vec<Count>> {
 fn gamma_rate(readings: &[Reading]) -> Result<u32> {
     let ds = _to_ds(readings)?;
     let ms = ds.iter().map(|c| c.max()).collect::<Vec<Bit>>();
-    let gamma = Reading(ms).as_number();
-    Ok(gamma)
+    Reading(ms).as_number()
 }
 
 fn epsilon_rate(readings: &[Reading]) -> Result<u32> {
     let ds = _to_ds(readings)?;
     let ms = ds.iter().map(|c| c.min()).collect::<Vec<Bit>>();
-    let epsilon = Reading(ms).as_number();
-    Ok(epsilon)
+    Reading(ms).as_number()
 }
 
-pub fn day3_1() -> Result<u32> {
-    let input = get_input("day3.txt")?;
-    let readings = parse_readings(&input)
-        .map_err(|_| anyhow!("parser error"))?
-        .1;
-    let gamma = gamma_rate(&readings)?;
-    let epsilon = epsilon_rate(&readings)?;
-    Ok(epsilon * gamma)
+fn read_readings(input: &str) -> Result<Vec<Reading>> {
+    finish(input, parse_readings(input))
+}
+
+fn part1(readings: &[Reading]) -> Result<u32> {
+    let gamma = gamma_rate(readings)?;
+    let epsilon = epsilon_rate(readings)?;
+    epsilon
+        .checked_mul(gamma)
+        .ok_or(Error::Overflow("power consumption"))
 }
 
 fn count_at(readings: &[Reading], pos: usize) -> Result<Count> {
@@ -182,7 +181,10 @@ fn get_rating(readings: &[Reading], decider_fn: impl Fn(&Count) -> Bit) -> Resul
         filtered = filter_by_bit_at(filtered.as_slice(), &decider, pos)?;
         pos += 1;
     }
-    Ok(filtered[0].as_number())
+    filtered
+        .first()
+        .ok_or_else(|| Error::invalid("no reading left to rate"))?
+        .as_number()
 }
 
 fn get_oxygen_rating(readings: &[Reading]) -> Result<u32> {
@@ -193,19 +195,46 @@ fn get_co2_rating(readings: &[Reading]) -> Result<u32> {
     get_rating(readings, |c| c.min())
 }
 
+fn part2(readings: &[Reading]) -> Result<u32> {
+    let oxygen = get_oxygen_rating(readings)?;
+    let co2 = get_co2_rating(readings)?;
+    oxygen
+        .checked_mul(co2)
+        .ok_or(Error::Overflow("life support rating"))
+}
+
+pub fn solve_part1(input: &str) -> Result<u32> {
+    part1(&read_readings(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u32> {
+    part2(&read_readings(input)?)
+}
+
+pub fn day3_1() -> Result<u32> {
+    solve_part1(&read_day(3)?)
+}
+
 pub fn day3_2() -> Result<u32> {
-    let input = get_input("day3.txt")?;
-    let readings = parse_readings(&input)
-        .map_err(|_| anyhow!("parser error"))?
-        .1;
-    let oxygen = get_oxygen_rating(&readings)?;
-    let co2 = get_co2_rating(&readings)?;
-    Ok(oxygen * co2)
+    solve_part2(&read_day(3)?)
+}
+
+static PART1: Puzzle<Vec<Reading>> = Puzzle::new(3, 1, "Binary Diagnostic", read_readings, |r| {
+    Ok(part1(&r)?.into())
+});
+static PART2: Puzzle<Vec<Reading>> = Puzzle::new(3, 2, "Binary Diagnostic", read_readings, |r| {
+    Ok(part2(&r)?.into())
+});
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Result;
 
     #[test]
     fn test_parse_reading() -> Result<()> {
@@ -228,8 +257,15 @@ mod tests {
     }
 
     #[test]
-    fn test_reading_as_number() {
+    fn test_reading_as_number() -> Result<()> {
         let r = Reading(vec![Bit::One, Bit::Zero, Bit::One, Bit::One, Bit::One]);
-        assert_eq!(23, r.as_number());
+        assert_eq!(23, r.as_number()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_reading_overflows() {
+        let r = Reading(vec![Bit::One; 33]);
+        assert!(r.as_number().is_err());
     }
 }
@@ -1,14 +1,11 @@
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{digit1, line_ending, space0, space1},
-    combinator::{eof, map_res},
-    multi::{count, separated_list0, separated_list1},
-    sequence::{preceded, terminated},
-    IResult,
+use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::grid::Grid;
+use crate::parsers::{
+    aligned_grid, blank_line, blocks, unsigned_comma_list, with_trailing_newlines,
 };
+use crate::solver::{Puzzle, Registry};
+use nom::{combinator::map, IResult};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct BingoSquare {
@@ -18,50 +15,16 @@ struct BingoSquare {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct BingoBoard {
-    squares: Vec<BingoSquare>,
-    x_length: usize,
-    y_length: usize,
+    squares: Grid<BingoSquare>,
 }
 
 type Balls = Vec<u32>;
 type Boards = Vec<BingoBoard>;
 
 impl BingoBoard {
-    fn lines(&self) -> Vec<Vec<&BingoSquare>> {
-        let mut ls = Vec::new();
-        let mut it = self.squares.iter();
-        let i = it.by_ref();
-        for _ in 0..self.y_length {
-            ls.push(i.take(self.x_length).collect());
-        }
-        ls
-    }
-    fn cols(&self) -> Vec<Vec<&BingoSquare>> {
-        let mut cs = Vec::new();
-        for x in 0..self.x_length {
-            let mut c = Vec::new();
-            for y in 0..self.y_length {
-                c.push(self.squares.get(x + (y * self.x_length)).unwrap());
-            }
-            cs.push(c);
-        }
-        cs
-    }
-    fn line_wins(line: &[&BingoSquare]) -> bool {
-        line.iter().all(|&sq| sq.hit)
-    }
     fn is_winner(&self) -> bool {
-        for line in self.lines().iter() {
-            if Self::line_wins(line) {
-                return true;
-            }
-        }
-        for col in self.cols().iter() {
-            if Self::line_wins(col) {
-                return true;
-            }
-        }
-        false
+        self.squares.rows().any(|line| line.iter().all(|sq| sq.hit))
+            || self.squares.columns().any(|mut col| col.all(|sq| sq.hit))
     }
     fn mark_hit(&mut self, num: &u32) {
         for b in self.squares.iter_mut() {
@@ -70,73 +33,33 @@ impl BingoBoard {
             }
         }
     }
-    fn sum_unmarked(&self) -> u32 {
+    fn sum_unmarked(&self) -> Option<u32> {
         self.squares
             .iter()
             .filter(|&sq| !sq.hit)
-            .map(|sq| sq.number)
-            .sum()
+            .try_fold(0u32, |acc, sq| acc.checked_add(sq.number))
     }
 }
 
-fn parse_balls(s: &str) -> IResult<&str, Balls> {
-    map_res(separated_list1(tag(","), digit1), |xs: Vec<&str>| {
-        xs.into_iter().map(|x| x.parse()).collect()
-    })(s)
-}
-
-fn parse_bingo_num(s: &str) -> IResult<&str, u32> {
-    map_res(preceded(space0, digit1), |s: &str| s.parse())(s)
-}
-
-fn parse_line(s: &str) -> IResult<&str, Vec<u32>> {
-    terminated(
-        separated_list1(space1, parse_bingo_num),
-        alt((eof, line_ending)),
-    )(s)
-}
-
 fn parse_board(s: &str) -> IResult<&str, BingoBoard> {
-    let (s, lines) = count(parse_line, 5)(s)?;
-    let y_length = lines.len();
-    let x_length = lines.get(0).unwrap().len();
-    let squares = lines
-        .into_iter()
-        .flatten()
-        .map(|v| BingoSquare {
-            number: v,
-            hit: false,
-        })
-        .collect();
-    Ok((
-        s,
-        BingoBoard {
-            squares,
-            y_length,
-            x_length,
-        },
-    ))
-}
-
-fn board_sep(s: &str) -> IResult<&str, &str> {
-    line_ending(s)
+    map(aligned_grid, |numbers: Grid<u32>| BingoBoard {
+        squares: numbers.map(|&number| BingoSquare { number, hit: false }),
+    })(s)
 }
 
 fn parse_boards(s: &str) -> IResult<&str, Boards> {
-    separated_list0(board_sep, parse_board)(s)
+    blocks(parse_board)(s)
 }
 
 fn parse_bingo(s: &str) -> IResult<&str, (Balls, Boards)> {
-    let (s, balls) = parse_balls(s)?;
-    let (s, _) = count(line_ending, 2)(s)?;
-    let (_, boards) = parse_boards(s)?;
+    let (s, balls) = unsigned_comma_list(s)?;
+    let (s, _) = blank_line(s)?;
+    let (s, boards) = with_trailing_newlines(parse_boards)(s)?;
     Ok((s, (balls, boards)))
 }
 
-fn get_bingo() -> Result<(Balls, Boards)> {
-    let input = get_input("day4.txt")?;
-    let (balls, boards) = parse_bingo(&input).map_err(|_| anyhow!("parser error"))?.1;
-    Ok((balls, boards))
+fn get_bingo(input: &str) -> Result<(Balls, Boards)> {
+    finish(input, parse_bingo(input))
 }
 
 fn get_winning_sum(balls: Balls, mut boards: Boards) -> Result<u32> {
@@ -144,11 +67,14 @@ fn get_winning_sum(balls: Balls, mut boards: Boards) -> Result<u32> {
         for board in boards.iter_mut() {
             board.mark_hit(ball);
             if board.is_winner() {
-                return Ok(board.sum_unmarked() * ball);
+                return board
+                    .sum_unmarked()
+                    .and_then(|sum| sum.checked_mul(*ball))
+                    .ok_or(Error::Overflow("final score"));
             }
         }
     }
-    Err(anyhow!("no winner!"))
+    Err(Error::invalid("no winner!"))
 }
 
 fn get_last_winner(balls: Balls, mut boards: Boards) -> Result<(u32, BingoBoard)> {
@@ -164,45 +90,79 @@ fn get_last_winner(balls: Balls, mut boards: Boards) -> Result<(u32, BingoBoard)
             }
         }
         if non_winners.is_empty() {
-            return Ok((ball, winners[winners.len() - 1].clone()));
+            let last = winners.pop().ok_or_else(|| Error::invalid("no boards!"))?;
+            return Ok((ball, last));
         }
         boards = non_winners;
     }
-    Err(anyhow!("no winner!"))
+    Err(Error::invalid("no winner!"))
 }
 
-pub fn day4_1() -> Result<u32> {
-    let (balls, boards) = get_bingo()?;
+fn part1((balls, boards): (Balls, Boards)) -> Result<u32> {
     get_winning_sum(balls, boards)
 }
 
-pub fn day4_2() -> Result<u32> {
-    let (balls, boards) = get_bingo()?;
+fn part2((balls, boards): (Balls, Boards)) -> Result<u32> {
     let (last_ball, last_winning_board) = get_last_winner(balls, boards)?;
-    Ok(last_ball * last_winning_board.sum_unmarked())
+    last_winning_board
+        .sum_unmarked()
+        .and_then(|sum| sum.checked_mul(last_ball))
+        .ok_or(Error::Overflow("final score"))
+}
+
+pub fn solve_part1(input: &str) -> Result<u32> {
+    part1(get_bingo(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u32> {
+    part2(get_bingo(input)?)
+}
+
+pub fn day4_1() -> Result<u32> {
+    solve_part1(&read_day(4)?)
+}
+
+pub fn day4_2() -> Result<u32> {
+    solve_part2(&read_day(4)?)
+}
+
+static PART1: Puzzle<(Balls, Boards)> =
+    Puzzle::new(4, 1, "Giant Squid", get_bingo, |b| Ok(part1(b)?.into()));
+static PART2: Puzzle<(Balls, Boards)> =
+    Puzzle::new(4, 2, "Giant Squid", get_bingo, |b| Ok(part2(b)?.into()));
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Result;
 
-    fn mk_test_board() -> BingoBoard {
+    fn board(width: usize, squares: Vec<BingoSquare>) -> BingoBoard {
         BingoBoard {
-            squares: (0..25)
+            squares: Grid::from_vec(width, squares).unwrap(),
+        }
+    }
+
+    fn mk_test_board() -> BingoBoard {
+        board(
+            5,
+            (0..25)
                 .map(|n| {
                     let h = n % 2 == 0;
                     BingoSquare { number: n, hit: h }
                 })
                 .collect(),
-            x_length: 5,
-            y_length: 5,
-        }
+        )
     }
     #[test]
     fn test_lines() {
         let b = mk_test_board();
-        let lines = b.lines();
-        let fst = vec![
+        let lines: Vec<Vec<&BingoSquare>> = b.squares.rows().map(|r| r.iter().collect()).collect();
+        let fst = [
             BingoSquare {
                 number: 0,
                 hit: true,
@@ -224,7 +184,7 @@ mod tests {
                 hit: true,
             },
         ];
-        let snd = vec![
+        let snd = [
             BingoSquare {
                 number: 5,
                 hit: false,
@@ -246,7 +206,7 @@ mod tests {
                 hit: false,
             },
         ];
-        let third = vec![
+        let third = [
             BingoSquare {
                 number: 10,
                 hit: true,
@@ -268,7 +228,7 @@ mod tests {
                 hit: true,
             },
         ];
-        let fourth = vec![
+        let fourth = [
             BingoSquare {
                 number: 15,
                 hit: false,
@@ -290,7 +250,7 @@ mod tests {
                 hit: false,
             },
         ];
-        let fifth = vec![
+        let fifth = [
             BingoSquare {
                 number: 20,
                 hit: true,
@@ -325,8 +285,8 @@ mod tests {
     #[test]
     fn test_cols() {
         let b = mk_test_board();
-        let cols = b.cols();
-        let fst = vec![
+        let cols: Vec<Vec<&BingoSquare>> = b.squares.columns().map(|c| c.collect()).collect();
+        let fst = [
             BingoSquare {
                 number: 0,
                 hit: true,
@@ -348,7 +308,7 @@ mod tests {
                 hit: true,
             },
         ];
-        let snd = vec![
+        let snd = [
             BingoSquare {
                 number: 1,
                 hit: false,
@@ -370,7 +330,7 @@ mod tests {
                 hit: false,
             },
         ];
-        let third = vec![
+        let third = [
             BingoSquare {
                 number: 2,
                 hit: true,
@@ -392,7 +352,7 @@ mod tests {
                 hit: true,
             },
         ];
-        let fourth = vec![
+        let fourth = [
             BingoSquare {
                 number: 3,
                 hit: false,
@@ -414,7 +374,7 @@ mod tests {
                 hit: false,
             },
         ];
-        let fifth = vec![
+        let fifth = [
             BingoSquare {
                 number: 4,
                 hit: true,
@@ -448,10 +408,9 @@ mod tests {
 
     #[test]
     fn test_line_wins() {
-        let b = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let b = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -489,16 +448,15 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         assert!(b.is_winner());
     }
 
     #[test]
     fn test_col_wins() {
-        let b = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let b = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -536,16 +494,15 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         assert!(b.is_winner());
     }
 
     #[test]
     fn test_not_winner() {
-        let b = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let b = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -583,16 +540,15 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         assert!(!b.is_winner());
     }
 
     #[test]
     fn test_mark_hit() {
-        let mut b = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let mut b = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -630,12 +586,11 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         b.mark_hit(&6);
-        let expected = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let expected = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -673,16 +628,15 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         assert_eq!(expected, b);
     }
 
     #[test]
     fn test_sum_unmarked() {
-        let b = BingoBoard {
-            x_length: 3,
-            y_length: 3,
-            squares: vec![
+        let b = board(
+            3,
+            vec![
                 BingoSquare {
                     number: 0,
                     hit: false,
@@ -720,8 +674,8 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
-        assert_eq!(25, b.sum_unmarked());
+        );
+        assert_eq!(Some(25), b.sum_unmarked());
     }
 
     #[test]
@@ -732,8 +686,8 @@ mod tests {
 15 16 17 18 19
 20 21 22 23 24"#;
         let (_, parsed) = parse_board(s)?;
-        assert_eq!(5, parsed.y_length);
-        assert_eq!(5, parsed.x_length);
+        assert_eq!(5, parsed.squares.height());
+        assert_eq!(5, parsed.squares.width());
         Ok(())
     }
 
@@ -752,10 +706,21 @@ mod tests {
 30 31 32 33 34"#;
         let (_, boards) = parse_boards(s)?;
         assert_eq!(2, boards.len(), "num_boards");
-        assert_eq!(5, boards[0].y_length);
-        assert_eq!(5, boards[1].y_length);
-        assert_eq!(5, boards[0].x_length);
-        assert_eq!(5, boards[1].x_length);
+        assert_eq!(5, boards[0].squares.height());
+        assert_eq!(5, boards[1].squares.height());
+        assert_eq!(5, boards[0].squares.width());
+        assert_eq!(5, boards[1].squares.width());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bingo_consumes_boards() -> Result<()> {
+        let s = "1,2\r\n\r\n1 2\r\n3 4\r\n\r\n5 6\r\n7 8\r\n\r\n";
+        let (rest, (balls, boards)) = parse_bingo(s)?;
+        assert_eq!("", rest);
+        assert_eq!(vec![1, 2], balls);
+        assert_eq!(2, boards.len());
+        assert!(get_bingo("1,2\n\n1 2\n3 4\n5\n").is_err());
         Ok(())
     }
 
@@ -780,7 +745,7 @@ mod tests {
 18  8 23 26 20
 22 11 13  6  5
  2  0 12  3  7"#;
-        let (balls, boards) = parse_bingo(s).map_err(|_| anyhow!("parser error"))?.1;
+        let (balls, boards) = finish(s, parse_bingo(s))?;
         let res = get_winning_sum(balls, boards)?;
         assert_eq!(4512, res);
         Ok(())
@@ -807,12 +772,11 @@ mod tests {
 18  8 23 26 20
 22 11 13  6  5
 2  0 12  3  7"#;
-        let (balls, boards) = parse_bingo(s).map_err(|_| anyhow!("parser error"))?.1;
+        let (balls, boards) = finish(s, parse_bingo(s))?;
         let (last_winning_ball, last_winning_board) = get_last_winner(balls, boards)?;
-        let expected_last_board = BingoBoard {
-            x_length: 5,
-            y_length: 5,
-            squares: vec![
+        let expected_last_board = board(
+            5,
+            vec![
                 BingoSquare {
                     number: 3,
                     hit: false,
@@ -914,10 +878,15 @@ mod tests {
                     hit: false,
                 },
             ],
-        };
+        );
         assert_eq!(13, last_winning_ball);
         assert_eq!(expected_last_board, last_winning_board);
-        assert_eq!(1924, last_winning_ball * last_winning_board.sum_unmarked());
+        assert_eq!(
+            Some(1924),
+            last_winning_board
+                .sum_unmarked()
+                .map(|sum| last_winning_ball * sum)
+        );
         Ok(())
     }
 }
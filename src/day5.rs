@@ -1,118 +1,65 @@
-use std::collections::HashMap;
-
-use anyhow::{anyhow, Result};
 use nom::{
-    bytes::complete::tag,
-    character::complete::{char, digit1, line_ending},
-    combinator::map_res,
-    multi::separated_list1,
-    sequence::separated_pair,
+    bytes::complete::tag, character::complete::char, combinator::map_res, sequence::separated_pair,
     IResult,
 };
 
-use crate::get_input::get_input;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-struct Point {
-    x: u32,
-    y: u32,
-}
-
-type Line = Vec<Point>;
+use std::convert::TryFrom;
+use std::io::BufRead;
+use std::iter::{once, successors};
 
-fn from_points_hv(start: Point, end: Point) -> Option<Line> {
-    if start.x == end.x {
-        Some(from_points_vertical(start, end))
-    } else if start.y == end.y {
-        Some(from_points_horizontal(start, end))
-    } else {
-        None
-    }
-}
+use crate::error::{Error, Result};
+use crate::geometry::{BoundingBox, Point2, Vec2};
+use crate::get_input::read_day;
+use crate::grid::Grid;
+use crate::parsers::integer;
+use crate::solver::{Puzzle, Registry};
+use crate::stream::{parse_str_lines, LineReader};
 
-fn from_points_hvd(start: Point, end: Point) -> Option<Line> {
-    from_points_hv(start, end).or_else(|| from_points_diag(start, end))
+/// A horizontal, vertical or 45 degree line of vents, both ends inclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Line {
+    start: Point2,
+    end: Point2,
 }
 
-fn from_points_vertical(start: Point, end: Point) -> Line {
-    let x = start.x;
-    let mut l = Vec::new();
-    let mut st = start.y;
-    let mut term = end.y + 1;
-    if start.y > end.y {
-        st = end.y;
-        term = start.y + 1;
-    }
-    for y in st..term {
-        l.push(Point { x, y });
-    }
-    l
-}
-fn from_points_horizontal(start: Point, end: Point) -> Line {
-    let y = start.y;
-    let mut l = Vec::new();
-    let mut st = start.x;
-    let mut term = end.x + 1;
-    if start.x > end.x {
-        st = end.x;
-        term = start.x + 1;
+impl Line {
+    /// The one-cell step from `start` towards `end`.
+    fn step(&self) -> Vec2 {
+        let towards = |a: i64, b: i64| b.cmp(&a) as i64;
+        Vec2::new(
+            towards(self.start.x, self.end.x),
+            towards(self.start.y, self.end.y),
+        )
     }
-    for x in st..term {
-        l.push(Point { x, y });
+    fn points(&self) -> impl Iterator<Item = Point2> {
+        let (end, step) = (self.end, self.step());
+        successors(Some(self.start), move |&p| (p != end).then(|| p + step))
     }
-    l
 }
 
-fn step_pos(v: u32) -> u32 {
-    v + 1
+fn from_points_hv(start: Point2, end: Point2) -> Option<Line> {
+    if start.x == end.x || start.y == end.y {
+        Some(Line { start, end })
+    } else {
+        None
+    }
 }
 
-fn step_neg(v: u32) -> u32 {
-    v.checked_sub(1).unwrap()
+fn from_points_hvd(start: Point2, end: Point2) -> Option<Line> {
+    from_points_hv(start, end).or_else(|| from_points_diag(start, end))
 }
 
-fn from_points_diag(start: Point, end: Point) -> Option<Line> {
-    let mut l = Vec::new();
-    let mut x_step = step_pos as fn(u32) -> u32;
-    let mut y_step = step_pos as fn(u32) -> u32;
-    let mut curr_x = start.x;
-    let mut curr_y = start.y;
-    let end_x = end.x;
-    let end_y = end.y;
-    if start.x > end.x {
-        x_step = step_neg;
-    }
-    if start.y > end.y {
-        y_step = step_neg;
-    }
-    l.push(start);
-    loop {
-        curr_x = x_step(curr_x);
-        curr_y = y_step(curr_y);
-        if (curr_x == end_x && (curr_y != end_y)) || (curr_y == end_y && (curr_x != end_x)) {
-            return None;
-        } else {
-            l.push(Point {
-                x: curr_x,
-                y: curr_y,
-            });
-            if curr_x == end_x && curr_y == end_y {
-                return Some(l);
-            }
-        }
+fn from_points_diag(start: Point2, end: Point2) -> Option<Line> {
+    if start.x.abs_diff(end.x) == start.y.abs_diff(end.y) {
+        Some(Line { start, end })
+    } else {
+        None
     }
 }
 
-fn parse_point(s: &str) -> IResult<&str, Point> {
-    map_res(
-        separated_pair(digit1, char(','), digit1),
-        |(x, y): (&str, &str)| {
-            Ok(Point {
-                x: x.parse().unwrap(),
-                y: y.parse().unwrap(),
-            }) as Result<_, Point>
-        },
-    )(s)
+fn parse_point(s: &str) -> IResult<&str, Point2> {
+    let (s, (x, y)) = separated_pair(integer, char(','), integer)(s)?;
+    Ok((s, Point2::new(x, y)))
 }
 
 fn arr(s: &str) -> IResult<&str, ()> {
@@ -133,147 +80,299 @@ fn parse_line_hvd(s: &str) -> IResult<&str, Option<Line>> {
     Ok((s, from_points_hvd(start, end)))
 }
 
-fn parse_lines_hv(s: &str) -> IResult<&str, Vec<Line>> {
-    map_res(separated_list1(line_ending, parse_line_hv), |v| {
-        Ok(v.into_iter().flatten().collect()) as Result<_, Vec<Line>>
-    })(s)
+/// The vents in `input` that `parse` keeps, read with the same line rules
+/// as [`count_streamed`].
+fn read_lines(input: &str, parse: fn(&str) -> IResult<&str, Option<Line>>) -> Result<Vec<Line>> {
+    Ok(parse_str_lines(input, parse)?
+        .into_iter()
+        .flatten()
+        .collect())
 }
 
-fn parse_lines_hvd(s: &str) -> IResult<&str, Vec<Line>> {
-    map_res(separated_list1(line_ending, parse_line_hvd), |v| {
-        Ok(v.into_iter().flatten().collect()) as Result<_, Vec<Line>>
-    })(s)
+/// The largest vent map built, in cells; puzzle inputs fit in 1000 by 1000.
+const MAX_MAP_CELLS: usize = 1 << 26;
+
+/// How many lines cross each point, counting up to 255, over a box around
+/// every line's ends.
+#[derive(Debug, Default)]
+struct Overlaps {
+    counts: Grid<u8>,
+    min: Point2,
 }
 
-#[derive(Debug)]
-struct Overlaps(HashMap<Point, usize>);
+/// `exact` widened by the size of `current` on each side where it is larger,
+/// so a map grown a line at a time is only copied a logarithmic number of
+/// times.
+fn padded(current: BoundingBox, exact: BoundingBox) -> BoundingBox {
+    let (w, h) = (current.width() as i64, current.height() as i64);
+    let lower = |e: i64, c: i64, by: i64| if e < c { e.saturating_sub(by) } else { e };
+    let upper = |e: i64, c: i64, by: i64| if e > c { e.saturating_add(by) } else { e };
+    BoundingBox {
+        min: Point2::new(
+            lower(exact.min.x, current.min.x, w),
+            lower(exact.min.y, current.min.y, h),
+        ),
+        max: Point2::new(
+            upper(exact.max.x, current.max.x, w),
+            upper(exact.max.y, current.max.y, h),
+        ),
+    }
+}
 
 impl Overlaps {
-    fn from_lines(lines: Vec<Line>) -> Self {
-        let mut m = HashMap::new();
-        for p in lines.iter().flatten() {
-            let c = m.entry(*p).or_insert(0);
-            *c += 1;
+    fn new(bounds: BoundingBox) -> Result<Self> {
+        if bounds.area().is_none_or(|a| a > MAX_MAP_CELLS as u64) {
+            return Err(Error::invalid(format!(
+                "vent map of {}x{} is too large",
+                bounds.width(),
+                bounds.height()
+            )));
+        }
+        let counts = Grid::new(bounds.width() as usize, bounds.height() as usize, 0)?;
+        Ok(Self {
+            counts,
+            min: bounds.min,
+        })
+    }
+    fn from_lines(lines: Vec<Line>) -> Result<Self> {
+        let ends = lines.iter().flat_map(|l| once(l.start).chain(once(l.end)));
+        let bounds = BoundingBox::around(ends).unwrap_or(BoundingBox::new(Point2::ORIGIN));
+        let mut o = Self::new(bounds)?;
+        for line in lines.iter() {
+            o.add(line)?;
+        }
+        Ok(o)
+    }
+    fn bounds(&self) -> Option<BoundingBox> {
+        let (w, h) = (self.counts.width() as i64, self.counts.height() as i64);
+        if w == 0 || h == 0 {
+            return None;
+        }
+        Some(BoundingBox::new(self.min).including(self.min + Vec2::new(w - 1, h - 1)))
+    }
+    /// Grows the map, keeping its counts, until it covers `needed`.
+    fn reserve(&mut self, needed: BoundingBox) -> Result<()> {
+        let current = match self.bounds() {
+            Some(b) if b.contains(needed.min) && b.contains(needed.max) => return Ok(()),
+            Some(b) => b,
+            None => {
+                *self = Self::new(needed)?;
+                return Ok(());
+            }
+        };
+        let exact = current.including(needed.min).including(needed.max);
+        let mut grown = Self::new(padded(current, exact)).or_else(|_| Self::new(exact))?;
+        for ((x, y), &ct) in self.counts.positions().filter(|(_, &ct)| ct > 0) {
+            if let Some(c) = grown.count_mut(self.min + Vec2::new(x as i64, y as i64)) {
+                *c = ct;
+            }
+        }
+        *self = grown;
+        Ok(())
+    }
+    fn count_mut(&mut self, p: Point2) -> Option<&mut u8> {
+        let x = usize::try_from(p.x.checked_sub(self.min.x)?).ok()?;
+        let y = usize::try_from(p.y.checked_sub(self.min.y)?).ok()?;
+        self.counts.get_mut(x, y)
+    }
+    fn add(&mut self, line: &Line) -> Result<()> {
+        self.reserve(BoundingBox::new(line.start).including(line.end))?;
+        for p in line.points() {
+            if let Some(c) = self.count_mut(p) {
+                *c = c.saturating_add(1);
+            }
         }
-        Self(m)
+        Ok(())
     }
-    fn filter_multi(&self) -> Vec<(&Point, &usize)> {
-        self.0.iter().filter(|(_, &ct)| ct > 1).collect()
+    fn filter_multi(&self) -> Vec<(Point2, u8)> {
+        self.counts
+            .positions()
+            .filter(|(_, &ct)| ct > 1)
+            .map(|((x, y), &ct)| (self.min + Vec2::new(x as i64, y as i64), ct))
+            .collect()
     }
     fn multi_count(&self) -> usize {
         self.filter_multi().len()
     }
 }
 
+/// Counts overlaps reading one line of vents at a time, so only the map is
+/// held in memory.
+fn count_streamed(
+    input: impl BufRead,
+    parse: fn(&str) -> IResult<&str, Option<Line>>,
+) -> Result<usize> {
+    let mut overlaps = Overlaps::default();
+    for line in LineReader::new(input).parse_lines(parse) {
+        if let Some(line) = line? {
+            overlaps.add(&line)?;
+        }
+    }
+    Ok(overlaps.multi_count())
+}
+
+/// Solves part 1 reading vents one line at a time.
+pub fn solve_part1_reader(input: impl BufRead) -> Result<usize> {
+    count_streamed(input, parse_line_hv)
+}
+
+/// Solves part 2 reading vents one line at a time.
+pub fn solve_part2_reader(input: impl BufRead) -> Result<usize> {
+    count_streamed(input, parse_line_hvd)
+}
+
+fn read_lines_hv(input: &str) -> Result<Vec<Line>> {
+    read_lines(input, parse_line_hv)
+}
+
+fn read_lines_hvd(input: &str) -> Result<Vec<Line>> {
+    read_lines(input, parse_line_hvd)
+}
+
+pub fn solve_part1(input: &str) -> Result<usize> {
+    Ok(Overlaps::from_lines(read_lines_hv(input)?)?.multi_count())
+}
+
+pub fn solve_part2(input: &str) -> Result<usize> {
+    Ok(Overlaps::from_lines(read_lines_hvd(input)?)?.multi_count())
+}
+
 pub fn day5_1() -> Result<usize> {
-    let input = get_input("day5.txt")?;
-    let (_, lines) = parse_lines_hv(&input).map_err(|_| anyhow!("parser error"))?;
-    Ok(Overlaps::from_lines(lines).multi_count())
+    solve_part1(&read_day(5)?)
 }
 
 pub fn day5_2() -> Result<usize> {
-    let input = get_input("day5.txt")?;
-    let (_, lines) = parse_lines_hvd(&input).map_err(|_| anyhow!("parser error"))?;
-    Ok(Overlaps::from_lines(lines).multi_count())
+    solve_part2(&read_day(5)?)
+}
+
+static PART1: Puzzle<Vec<Line>> = Puzzle::new(5, 1, "Hydrothermal Venture", read_lines_hv, |l| {
+    Ok(Overlaps::from_lines(l)?.multi_count().into())
+})
+.with_stream(|r| Ok(solve_part1_reader(r)?.into()));
+static PART2: Puzzle<Vec<Line>> = Puzzle::new(5, 2, "Hydrothermal Venture", read_lines_hvd, |l| {
+    Ok(Overlaps::from_lines(l)?.multi_count().into())
+})
+.with_stream(|r| Ok(solve_part2_reader(r)?.into()));
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use anyhow::Result;
+
+    fn p(x: i64, y: i64) -> Point2 {
+        Point2::new(x, y)
+    }
+
+    fn points(l: Line) -> Vec<Point2> {
+        l.points().collect()
+    }
+
+    fn lines() -> Vec<Line> {
+        vec![
+            from_points_hv(p(1, 1), p(3, 1)).unwrap(),
+            from_points_hv(p(1, 1), p(1, 3)).unwrap(),
+            from_points_hv(p(1, 2), p(3, 2)).unwrap(),
+        ]
+    }
 
     #[test]
     fn test_lines() {
-        let s0 = Point { x: 1, y: 1 };
-        let e0 = Point { x: 1, y: 3 };
-        let expected_0 = vec![
-            Point { x: 1, y: 1 },
-            Point { x: 1, y: 2 },
-            Point { x: 1, y: 3 },
-        ];
-        let actual_0 = from_points_hv(s0, e0).unwrap();
-        assert_eq!(expected_0, actual_0);
-        let s1 = Point { x: 9, y: 7 };
-        let e1 = Point { x: 7, y: 7 };
-        let expected_1 = vec![
-            Point { x: 7, y: 7 },
-            Point { x: 8, y: 7 },
-            Point { x: 9, y: 7 },
-        ];
-        let actual_1 = from_points_hv(s1, e1).unwrap();
-        assert_eq!(expected_1, actual_1);
+        let actual_0 = from_points_hv(p(1, 1), p(1, 3)).unwrap();
+        assert_eq!(vec![p(1, 1), p(1, 2), p(1, 3)], points(actual_0));
+        let actual_1 = from_points_hv(p(9, 7), p(7, 7)).unwrap();
+        assert_eq!(vec![p(9, 7), p(8, 7), p(7, 7)], points(actual_1));
+        let single = from_points_hv(p(4, 4), p(4, 4)).unwrap();
+        assert_eq!(vec![p(4, 4)], points(single));
+        assert_eq!(None, from_points_hv(p(0, 0), p(1, 1)));
     }
 
     #[test]
-    fn test_overlaps_from_lines() {
-        let lines = vec![
-            vec![
-                Point { x: 1, y: 1 },
-                Point { x: 2, y: 1 },
-                Point { x: 3, y: 1 },
-            ],
-            vec![
-                Point { x: 1, y: 1 },
-                Point { x: 1, y: 2 },
-                Point { x: 1, y: 3 },
-            ],
-            vec![
-                Point { x: 1, y: 2 },
-                Point { x: 2, y: 2 },
-                Point { x: 3, y: 2 },
-            ],
-        ];
-        let o = Overlaps::from_lines(lines);
-        let expected_m: HashMap<Point, usize> = [
-            (Point { x: 1, y: 1 }, 2),
-            (Point { x: 2, y: 1 }, 1),
-            (Point { x: 3, y: 1 }, 1),
-            (Point { x: 1, y: 2 }, 2),
-            (Point { x: 1, y: 3 }, 1),
-            (Point { x: 2, y: 2 }, 1),
-            (Point { x: 3, y: 2 }, 1),
-        ]
-        .into();
-        assert_eq!(expected_m, o.0);
+    fn test_overlaps_from_lines() -> Result<()> {
+        let o = Overlaps::from_lines(lines())?;
+        let expected_m = Grid::from_rows(vec![vec![2, 1, 1], vec![2, 1, 1], vec![1, 0, 0]])?;
+        assert_eq!(expected_m, o.counts);
+        assert_eq!(p(1, 1), o.min);
+        Ok(())
     }
 
     #[test]
-    fn test_overlaps_filter_multi() {
-        let lines = vec![
-            vec![
-                Point { x: 1, y: 1 },
-                Point { x: 2, y: 1 },
-                Point { x: 3, y: 1 },
-            ],
-            vec![
-                Point { x: 1, y: 1 },
-                Point { x: 1, y: 2 },
-                Point { x: 1, y: 3 },
-            ],
-            vec![
-                Point { x: 1, y: 2 },
-                Point { x: 2, y: 2 },
-                Point { x: 3, y: 2 },
-            ],
-        ];
-        let o = Overlaps::from_lines(lines);
-        let expected_filtered = vec![(&Point { x: 1, y: 1 }, &2), (&Point { x: 1, y: 2 }, &2)];
+    fn test_overlaps_filter_multi() -> Result<()> {
+        let o = Overlaps::from_lines(lines())?;
+        let expected_filtered = vec![(p(1, 1), 2), (p(1, 2), 2)];
         let mut filtered = o.filter_multi();
         filtered.sort_by_key(|v| v.0);
         assert_eq!(filtered, expected_filtered);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_coordinates() -> Result<()> {
+        let input = "-2,-2 -> 2,2\n-2,2 -> 2,-2\n0,-5 -> 0,5\n-3,0 -> 3,0\n";
+        assert_eq!(1, solve_part1(input)?);
+        assert_eq!(1, solve_part2(input)?);
+        let o = Overlaps::from_lines(read_lines_hvd(input)?)?;
+        assert_eq!(vec![(p(0, 0), 4)], o.filter_multi());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_from_reader() -> Result<()> {
+        let input = "-2,-2 -> 2,2\r\n-2,2 -> 2,-2\n0,-5 -> 0,5\n-3,0 -> 3,0\n";
+        assert_eq!(1, solve_part1_reader(input.as_bytes())?);
+        assert_eq!(1, solve_part2_reader(input.as_bytes())?);
+        let grown: String = (0..40)
+            .map(|i| format!("{},{} -> {},{}\n", -i, i, i, i))
+            .collect();
+        let grown = format!("0,0 -> 0,39\n{}", grown);
+        assert_eq!(40, solve_part1_reader(grown.as_bytes())?);
+        assert_eq!(solve_part1(&grown)?, solve_part1_reader(grown.as_bytes())?);
+        assert!(solve_part1_reader("0,0 -> 1,1\n0,0 -> 0,x\n".as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_accepts_same_input() {
+        let inputs = [
+            "0,0 -> 0,2\n\n0,1 -> 2,1\n \n",
+            "0,0 -> 0,2\r\n0,1 -> 2,1\n",
+            "0,0 -> 0,2\n0,1  -> 2,1\n",
+            "0,0 -> 0,2\n0,1 -> 2,x\n",
+        ];
+        for input in inputs {
+            let read = |s: crate::error::Result<usize>| s.map_err(|e| e.to_string());
+            assert_eq!(
+                read(solve_part1(input)),
+                read(solve_part1_reader(input.as_bytes())),
+                "{:?}",
+                input
+            );
+            assert_eq!(
+                read(solve_part2(input)),
+                read(solve_part2_reader(input.as_bytes())),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(1, solve_part1(inputs[0]).unwrap());
+    }
+
+    #[test]
+    fn test_huge_map_is_rejected() {
+        assert!(solve_part1("0,0 -> 0,9223372036854775807\n").is_err());
+        assert!(solve_part1("-9223372036854775808,0 -> 9223372036854775807,0\n").is_err());
     }
 
     #[test]
     fn test_parse_line_h() -> Result<()> {
         let s = "100,100 -> 103,100";
-        let expected = vec![
-            Point { x: 100, y: 100 },
-            Point { x: 101, y: 100 },
-            Point { x: 102, y: 100 },
-            Point { x: 103, y: 100 },
-        ];
+        let expected = vec![p(100, 100), p(101, 100), p(102, 100), p(103, 100)];
         let (_, l) = parse_line_hv(s)?;
-        let l = l.unwrap();
-        assert_eq!(expected, l);
+        assert_eq!(expected, points(l.unwrap()));
         Ok(())
     }
 
@@ -283,70 +382,35 @@ mod test {
 200,203 -> 200,206
 203,203 -> 204,210"#;
         let expected = vec![
-            vec![
-                Point { x: 100, y: 100 },
-                Point { x: 101, y: 100 },
-                Point { x: 102, y: 100 },
-                Point { x: 103, y: 100 },
-            ],
-            vec![
-                Point { x: 200, y: 203 },
-                Point { x: 200, y: 204 },
-                Point { x: 200, y: 205 },
-                Point { x: 200, y: 206 },
-            ],
+            Line {
+                start: p(100, 100),
+                end: p(103, 100),
+            },
+            Line {
+                start: p(200, 203),
+                end: p(200, 206),
+            },
         ];
-        let (_, actual) = parse_lines_hv(s)?;
+        let actual = read_lines_hv(s)?;
         assert_eq!(expected, actual);
         Ok(())
     }
 
     #[test]
     fn test_from_points_diag() {
-        let s = Point { x: 5, y: 5 };
-        let e_both_pos = Point { x: 10, y: 10 };
-        let expected_both_pos = vec![
-            Point { x: 5, y: 5 },
-            Point { x: 6, y: 6 },
-            Point { x: 7, y: 7 },
-            Point { x: 8, y: 8 },
-            Point { x: 9, y: 9 },
-            Point { x: 10, y: 10 },
-        ];
-        let actual_both_pos = from_points_diag(s, e_both_pos).expect("diag both pos is None");
-        assert_eq!(actual_both_pos, expected_both_pos, "diag both pos");
-        let e_x_neg = Point { x: 0, y: 10 };
-        let expected_x_neg = vec![
-            Point { x: 5, y: 5 },
-            Point { x: 4, y: 6 },
-            Point { x: 3, y: 7 },
-            Point { x: 2, y: 8 },
-            Point { x: 1, y: 9 },
-            Point { x: 0, y: 10 },
+        let s = p(5, 5);
+        let cases = vec![
+            (p(10, 10), "diag both pos"),
+            (p(0, 10), "diag x neg"),
+            (p(10, 0), "diag y neg"),
+            (p(0, 0), "diag both neg"),
         ];
-        let actual_x_neg = from_points_diag(s, e_x_neg).expect("diag x neg is None");
-        assert_eq!(expected_x_neg, actual_x_neg, "diag x neg");
-        let e_y_neg = Point { x: 10, y: 0 };
-        let expected_y_neg = vec![
-            Point { x: 5, y: 5 },
-            Point { x: 6, y: 4 },
-            Point { x: 7, y: 3 },
-            Point { x: 8, y: 2 },
-            Point { x: 9, y: 1 },
-            Point { x: 10, y: 0 },
-        ];
-        let actual_y_neg = from_points_diag(s, e_y_neg).expect("diag y neg is None");
-        assert_eq!(expected_y_neg, actual_y_neg, "diag y neg");
-        let e_both_neg = Point { x: 0, y: 0 };
-        let expected_both_neg = vec![
-            Point { x: 5, y: 5 },
-            Point { x: 4, y: 4 },
-            Point { x: 3, y: 3 },
-            Point { x: 2, y: 2 },
-            Point { x: 1, y: 1 },
-            Point { x: 0, y: 0 },
-        ];
-        let actual_both_neg = from_points_diag(s, e_both_neg).expect("diag both neg is None");
-        assert_eq!(expected_both_neg, actual_both_neg, "diag both neg");
+        for (e, name) in cases {
+            let actual = points(from_points_diag(s, e).expect(name));
+            let step = (e - s).signum();
+            let expected: Vec<Point2> = (0..=5).map(|i| s + step * i).collect();
+            assert_eq!(expected, actual, "{}", name);
+        }
+        assert_eq!(None, from_points_diag(s, p(7, 8)));
     }
 }
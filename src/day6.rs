@@ -1,9 +1,9 @@
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
-use nom::{
-    bytes::complete::tag, character::complete::digit1, combinator::map_res, multi::separated_list1,
-    IResult,
-};
+pub use crate::day6_2::solve_part2;
+use crate::error::{finish, Result};
+use crate::get_input::read_day;
+use crate::parsers::{unsigned_comma_list, with_trailing_newlines};
+use crate::solver::{Puzzle, Registry};
+use nom::{combinator::map, IResult};
 
 #[derive(Debug, Eq, PartialEq)]
 struct LF(u8);
@@ -49,29 +49,31 @@ impl LanternFish {
     }
 }
 
-pub fn parse_nums(s: &str) -> IResult<&str, Vec<u8>> {
-    map_res(separated_list1(tag(","), digit1), |xs: Vec<&str>| {
-        xs.into_iter()
-            .map(|x| x.parse().map_err(|_| anyhow!("parse error")))
-            .collect::<Result<Vec<u8>>>()
-    })(s)
+fn parse_fish(s: &str) -> IResult<&str, LanternFish> {
+    map(
+        with_trailing_newlines(unsigned_comma_list),
+        LanternFish::from_nums,
+    )(s)
 }
 
-fn parse_fish(s: &str) -> IResult<&str, LanternFish> {
-    let (s, f) = parse_nums(s)?;
-    Ok((s, LanternFish::from_nums(f)))
+fn read_fish(input: &str) -> Result<LanternFish> {
+    finish(input, parse_fish(input))
 }
 
-fn read_fish() -> anyhow::Result<LanternFish> {
-    let s = get_input("day6.txt")?;
-    parse_fish(&s)
-        .map_err(|_| anyhow!("parsing error"))
-        .map(|(_, fs)| fs)
+pub fn solve_part1(input: &str) -> Result<usize> {
+    Ok(read_fish(input)?.tick_n(80))
 }
 
-pub fn day6_1() -> anyhow::Result<usize> {
-    let mut fish = read_fish()?;
-    Ok(fish.tick_n(80))
+pub fn day6_1() -> Result<usize> {
+    solve_part1(&read_day(6)?)
+}
+
+static PART1: Puzzle<LanternFish> = Puzzle::new(6, 1, "Lanternfish", read_fish, |mut f| {
+    Ok(f.tick_n(80).into())
+});
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
 }
 
 #[cfg(test)]
@@ -1,64 +1,72 @@
-use crate::day6::parse_nums;
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::parsers::{unsigned_comma_list, with_trailing_newlines};
+use crate::solver::{Puzzle, Registry};
 
+/// Number of fish with each timer value, indexed by timer.
 #[derive(Debug, Eq, PartialEq)]
-struct FH(HashMap<u8, usize>);
+struct FH([usize; 9]);
 
 impl FH {
-    fn from_nums(ns: Vec<u8>) -> Self {
-        let mut m = HashMap::from([
-            (0, 0),
-            (1, 0),
-            (2, 0),
-            (3, 0),
-            (4, 0),
-            (5, 0),
-            (6, 0),
-            (7, 0),
-            (8, 0),
-        ]);
-        for n in ns.iter() {
-            *m.get_mut(n).unwrap() += 1;
+    fn from_nums(ns: Vec<u8>) -> Result<Self> {
+        let mut m = [0; 9];
+        for n in ns.into_iter() {
+            let count = m
+                .get_mut(usize::from(n))
+                .ok_or_else(|| Error::invalid(format!("fish timer {} is above 8", n)))?;
+            *count += 1;
         }
-        Self(m)
+        Ok(Self(m))
     }
     fn tick(&mut self) {
-        let curr = self.0.clone();
-        let new = curr.get(&0).unwrap();
-        for i in 0..8 {
-            if i == 6 {
-                *self.0.get_mut(&i).unwrap() = curr.get(&7).unwrap() + new;
-            } else {
-                *self.0.get_mut(&i).unwrap() = *curr.get(&(i + 1)).unwrap();
-            }
-        }
-        *self.0.get_mut(&8).unwrap() = *new;
+        self.0.rotate_left(1);
+        self.0[6] += self.0[8];
     }
     fn tick_n(&mut self, days: u32) -> usize {
         for _ in 0..days {
             self.tick();
         }
-        self.0.values().sum()
+        self.0.iter().sum()
     }
 }
 
+fn read_fh(input: &str) -> Result<FH> {
+    FH::from_nums(finish(
+        input,
+        with_trailing_newlines(unsigned_comma_list)(input),
+    )?)
+}
+
+pub fn solve_part2(input: &str) -> Result<usize> {
+    Ok(read_fh(input)?.tick_n(256))
+}
+
 pub fn day6_2() -> Result<usize> {
-    let input = get_input("day6.txt")?;
-    let (_, ns) = parse_nums(&input).map_err(|_| anyhow!("parse err"))?;
-    let mut fish = FH::from_nums(ns);
-    Ok(fish.tick_n(256))
+    solve_part2(&read_day(6)?)
+}
+
+static PART2: Puzzle<FH> = Puzzle::new(6, 2, "Lanternfish", read_fh, |mut f| {
+    Ok(f.tick_n(256).into())
+});
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
-    fn test_tick_1() {
-        let mut start = FH::from_nums(vec![3, 4, 3, 1, 2]);
-        let expected = FH::from_nums(vec![2, 3, 2, 0, 1]);
+    fn test_tick_1() -> Result<()> {
+        let mut start = FH::from_nums(vec![3, 4, 3, 1, 2])?;
+        let expected = FH::from_nums(vec![2, 3, 2, 0, 1])?;
         start.tick();
         assert_eq!(start, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_timer_out_of_range() {
+        assert!(FH::from_nums(vec![3, 9]).is_err());
     }
 }
@@ -1,124 +1,146 @@
-use crate::get_input::get_input;
-use anyhow::{anyhow, Result};
-use nom::{
-    bytes::complete::tag, character::complete::digit1, combinator::map_res, multi::separated_list1,
-    IResult,
-};
+use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::parsers::{unsigned_comma_list, with_trailing_newlines};
+use crate::solver::{Puzzle, Registry};
 use std::collections::HashMap;
-
-fn parse_positions(s: &str) -> IResult<&str, Vec<i32>> {
-    map_res(separated_list1(tag(","), digit1), |xs: Vec<&str>| {
-        xs.into_iter()
-            .map(|x| x.parse().map_err(|_| anyhow!("parse error")))
-            .collect::<Result<Vec<i32>>>()
-    })(s)
-}
+use std::convert::TryFrom;
 
 fn fuel_needed(start: &i32, end: &i32) -> i32 {
     (start - end).abs()
 }
 
-fn total_for_start(start: &i32, others: &[i32]) -> i32 {
-    let mut total = 0;
-    for v in others.iter() {
-        total += fuel_needed(start, v);
-    }
-    total
+fn total_for_start(start: &i32, others: &[i32]) -> Result<i32> {
+    others
+        .iter()
+        .try_fold(0i32, |total, v| total.checked_add(fuel_needed(start, v)))
+        .ok_or(Error::Overflow("fuel needed"))
 }
 
 type FuelNeeds = HashMap<i32, i32>;
 
-fn get_needed_fuel(starting_positions: Vec<i32>) -> FuelNeeds {
+fn get_needed_fuel(starting_positions: Vec<i32>) -> Result<FuelNeeds> {
     let mut m = HashMap::new();
     for n in starting_positions.iter() {
-        if m.get(n).is_none() {
-            m.insert(*n, total_for_start(n, &starting_positions));
+        if !m.contains_key(n) {
+            m.insert(*n, total_for_start(n, &starting_positions)?);
         }
     }
-    m
+    Ok(m)
 }
 
-fn cheapest_cost(costs: FuelNeeds) -> i32 {
-    let mut curr_cost = i32::MAX;
-    for cost in costs.values() {
-        if cost < &curr_cost {
-            curr_cost = *cost;
-        }
-    }
-    curr_cost
+fn cheapest_cost(costs: FuelNeeds) -> Result<i32> {
+    costs
+        .values()
+        .copied()
+        .min()
+        .ok_or_else(|| Error::invalid("no crab positions"))
 }
 
-fn get_positions() -> Result<Vec<i32>> {
-    let input = get_input("day7.txt")?;
-    let (_, positions) = parse_positions(&input).map_err(|_| anyhow!("parse error"))?;
-    Ok(positions)
+fn get_positions(input: &str) -> Result<Vec<i32>> {
+    finish(input, with_trailing_newlines(unsigned_comma_list)(input))
 }
 
-pub fn day7_1() -> Result<i32> {
-    let positions = get_positions()?;
-    let costs = get_needed_fuel(positions);
-    Ok(cheapest_cost(costs))
+fn part1(positions: Vec<i32>) -> Result<i32> {
+    cheapest_cost(get_needed_fuel(positions)?)
 }
 
-fn part2_cost(start: &i32, end: &i32) -> i32 {
-    if start == end {
-        0
-    } else {
-        let end = (start - end).abs() + 1;
-        (1..end).sum()
-    }
+pub fn solve_part1(input: &str) -> Result<i32> {
+    part1(get_positions(input)?)
 }
 
-fn total_for_start_2(start: &i32, others: &[i32]) -> i32 {
-    let mut total = 0;
-    for v in others.iter() {
-        total += part2_cost(start, v);
-    }
-    total
+fn part2_cost(start: &i32, end: &i32) -> Option<i32> {
+    let d = i64::from(*start) - i64::from(*end);
+    let d = d.abs();
+    i32::try_from(d * (d + 1) / 2).ok()
 }
 
-fn get_needed_fuel_2(starting_positions: Vec<i32>) -> FuelNeeds {
-    let mut m = HashMap::new();
-    let max = starting_positions
+fn total_for_start_2(start: &i32, others: &[i32]) -> Result<i32> {
+    others
         .iter()
-        .fold(&i32::MIN, |a, v| if v > a { v } else { a });
-    for n in 0..*max {
-        if m.get(&n).is_none() {
-            m.insert(n, total_for_start_2(&n, &starting_positions));
-        }
+        .try_fold(0i32, |total, v| total.checked_add(part2_cost(start, v)?))
+        .ok_or(Error::Overflow("fuel needed"))
+}
+
+fn get_needed_fuel_2(starting_positions: Vec<i32>) -> Result<FuelNeeds> {
+    let mut m = HashMap::new();
+    let min = starting_positions.iter().copied().min().unwrap_or(0);
+    let max = starting_positions.iter().copied().max().unwrap_or(0);
+    for n in min..=max {
+        m.insert(n, total_for_start_2(&n, &starting_positions)?);
     }
-    m
+    Ok(m)
+}
+
+fn part2(positions: Vec<i32>) -> Result<i32> {
+    cheapest_cost(get_needed_fuel_2(positions)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<i32> {
+    part2(get_positions(input)?)
+}
+
+pub fn day7_1() -> Result<i32> {
+    solve_part1(&read_day(7)?)
 }
 
 pub fn day7_2() -> Result<i32> {
-    let positions = get_positions()?;
-    let costs = get_needed_fuel_2(positions);
-    Ok(cheapest_cost(costs))
+    solve_part2(&read_day(7)?)
+}
+
+static PART1: Puzzle<Vec<i32>> = Puzzle::new(7, 1, "The Treachery of Whales", get_positions, |p| {
+    Ok(part1(p)?.into())
+});
+static PART2: Puzzle<Vec<i32>> = Puzzle::new(7, 2, "The Treachery of Whales", get_positions, |p| {
+    Ok(part2(p)?.into())
+});
+
+pub fn register(registry: &mut Registry) {
+    registry.register(&PART1);
+    registry.register(&PART2);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
-    fn test_cheapest_cost() {
+    fn test_cheapest_cost() -> Result<()> {
         let positions = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
-        let costs = get_needed_fuel(positions);
-        assert_eq!(37, cheapest_cost(costs));
+        let costs = get_needed_fuel(positions)?;
+        assert_eq!(37, cheapest_cost(costs)?);
+        Ok(())
     }
     #[test]
     fn test_part2_cost() {
-        assert_eq!(66, part2_cost(&16, &5));
-        assert_eq!(10, part2_cost(&1, &5));
-        assert_eq!(6, part2_cost(&2, &5));
-        assert_eq!(15, part2_cost(&0, &5));
-        assert_eq!(1, part2_cost(&4, &5));
-        assert_eq!(3, part2_cost(&7, &5));
-        assert_eq!(45, part2_cost(&14, &5));
+        assert_eq!(Some(66), part2_cost(&16, &5));
+        assert_eq!(Some(10), part2_cost(&1, &5));
+        assert_eq!(Some(6), part2_cost(&2, &5));
+        assert_eq!(Some(15), part2_cost(&0, &5));
+        assert_eq!(Some(1), part2_cost(&4, &5));
+        assert_eq!(Some(3), part2_cost(&7, &5));
+        assert_eq!(Some(45), part2_cost(&14, &5));
     }
     #[test]
-    fn test_cheapest_cost_2() {
+    fn test_cheapest_cost_2() -> Result<()> {
         let positions = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
-        let costs = get_needed_fuel_2(positions);
-        assert_eq!(168, cheapest_cost(costs));
+        let costs = get_needed_fuel_2(positions)?;
+        assert_eq!(168, cheapest_cost(costs)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_negative_positions() -> Result<()> {
+        assert!(solve_part2("-5,-3").is_err());
+        assert!(solve_part2("-5,3").is_err());
+        assert!(solve_part1("5,-3").is_err());
+        assert_eq!(20, part2(vec![-5, 3])?);
+        assert_eq!(2, part2(vec![-5, -3])?);
+        assert!(cheapest_cost(FuelNeeds::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuel_overflow() {
+        assert!(solve_part1("0,2147483647,2147483647").is_err());
+        assert_eq!(None, part2_cost(&0, &2147483647));
     }
 }
@@ -0,0 +1,159 @@
+use nom::IResult;
+use thiserror::Error;
+
+use std::io;
+
+use crate::get_input::InputError;
+
+const SNIPPET_LEN: usize = 40;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Input(#[from] InputError),
+    #[error("parse error at line {line}, column {column}: {message}: {snippet:?}")]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+        snippet: String,
+    },
+    #[error("invalid puzzle state: {0}")]
+    InvalidState(String),
+    #[error("overflow computing {0}")]
+    Overflow(&'static str),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// A parse error at the position where `at`, a slice of `input`,
+    /// begins. Slices from elsewhere are reported at the end of `input`.
+    pub fn parse(input: &str, at: &str, message: impl Into<String>) -> Self {
+        let start = input.as_ptr() as usize;
+        let mut offset = (at.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| *o <= input.len())
+            .unwrap_or(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+        let snippet = input[line_start..]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .chars()
+            .take(SNIPPET_LEN)
+            .collect();
+        Self::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+            snippet,
+        }
+    }
+    /// Moves a parse error in a single line of input to line `line` of the
+    /// whole input, leaving other errors alone.
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::Parse {
+                column,
+                message,
+                snippet,
+                ..
+            } => Self::Parse {
+                line,
+                column,
+                message,
+                snippet,
+            },
+            e => e,
+        }
+    }
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::InvalidState(message.into())
+    }
+    pub fn from_nom(input: &str, e: nom::Err<nom::error::Error<&str>>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => {
+                Self::parse(input, &input[input.len()..], "unexpected end of input")
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self::parse(
+                input,
+                e.input,
+                format!("unexpected input ({})", e.code.description()),
+            ),
+        }
+    }
+}
+
+/// Converts the result of running a nom parser over `input` into a crate
+/// result, locating any failure within `input`. Anything other than
+/// whitespace left over by the parser is an error too.
+pub fn finish<'a, T>(input: &'a str, result: IResult<&'a str, T>) -> Result<T> {
+    let (rest, v) = result.map_err(|e| Error::from_nom(input, e))?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(Error::parse(input, rest, "unparsed input"));
+    }
+    Ok(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::digit1;
+
+    #[test]
+    fn test_parse_position() {
+        let input = "10,10 -> 20,20\n3,4 -> x,2\n";
+        let rest = &input[22..];
+        match Error::parse(input, rest, "bad point") {
+            Error::Parse {
+                line,
+                column,
+                message,
+                snippet,
+            } => {
+                assert_eq!(2, line);
+                assert_eq!(8, column);
+                assert_eq!("bad point", message);
+                assert_eq!("3,4 -> x,2", snippet);
+            }
+            e => panic!("unexpected {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_finish() {
+        let input = "abc";
+        let e = finish(input, digit1::<_, nom::error::Error<&str>>(input)).unwrap_err();
+        assert_eq!(
+            "parse error at line 1, column 1: unexpected input (Digit): \"abc\"",
+            e.to_string()
+        );
+        assert_eq!(
+            "12",
+            finish("12", digit1::<_, nom::error::Error<&str>>("12")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_finish_rejects_leftovers() {
+        let input = "12\r\n \n";
+        assert_eq!(
+            "12",
+            finish(input, digit1::<_, nom::error::Error<&str>>(input)).unwrap()
+        );
+        let input = "12\r\n34\n";
+        let e = finish(input, digit1::<_, nom::error::Error<&str>>(input)).unwrap_err();
+        assert_eq!(
+            "parse error at line 2, column 1: unparsed input: \"34\"",
+            e.to_string()
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::get_input::{InputError, InputProvider, DEFAULT_YEAR};
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("unsupported url {0:?}; only http://host[:port][/path] is supported")]
+    BadUrl(String),
+    #[error(
+        "https is not supported ({0}); fetch from a plain http:// mirror or a fixture directory"
+    )]
+    HttpsUnsupported(String),
+    #[error("server answered {status} for {url}")]
+    Status { url: String, status: u16 },
+    #[error("malformed response from {0}")]
+    BadResponse(String),
+}
+
+pub trait Fetcher: Send + Sync {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError>;
+    /// Short description recorded in the cache metadata.
+    fn source(&self) -> String;
+}
+
+/// Picks a fetcher from a spec: `http://...` for `HttpFetcher`, anything
+/// else is treated as a fixture directory. `https://` is rejected, as there
+/// is no TLS support.
+pub fn fetcher_from_spec(
+    spec: &str,
+    session: Option<String>,
+) -> Result<Box<dyn Fetcher>, FetchError> {
+    if spec.starts_with("https://") {
+        Err(FetchError::HttpsUnsupported(spec.to_string()))
+    } else if spec.starts_with("http://") {
+        Ok(Box::new(HttpFetcher::new(spec, session)))
+    } else {
+        Ok(Box::new(FixtureFetcher::new(spec)))
+    }
+}
+
+/// Serves inputs from `<dir>/<year>/dayN.txt` or `<dir>/dayN.txt`, so the
+/// cache can be exercised without a network.
+pub struct FixtureFetcher {
+    dir: PathBuf,
+}
+
+impl FixtureFetcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Fetcher for FixtureFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        let file = format!("day{}.txt", day);
+        let nested = self.dir.join(year.to_string()).join(&file);
+        if nested.is_file() {
+            Ok(fs::read_to_string(nested)?)
+        } else {
+            Ok(fs::read_to_string(self.dir.join(file))?)
+        }
+    }
+    fn source(&self) -> String {
+        self.dir.display().to_string()
+    }
+}
+
+/// Minimal plain-HTTP client requesting `<base>/<year>/day/<day>/input`, the
+/// same path layout as adventofcode.com. It does not speak TLS; it is meant
+/// to be pointed at a local mirror or mock server.
+pub struct HttpFetcher {
+    base_url: String,
+    session: Option<String>,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+    fn split_url(&self) -> Result<(&str, String), FetchError> {
+        let rest = self
+            .base_url
+            .strip_prefix("http://")
+            .ok_or_else(|| FetchError::BadUrl(self.base_url.clone()))?;
+        let (host, path) = match rest.find('/') {
+            Some(ix) => (&rest[..ix], rest[ix..].to_string()),
+            None => (rest, String::new()),
+        };
+        if host.is_empty() {
+            return Err(FetchError::BadUrl(self.base_url.clone()));
+        }
+        Ok((host, path))
+    }
+}
+
+fn parse_response(url: &str, raw: &[u8]) -> Result<String, FetchError> {
+    let bad = || FetchError::BadResponse(url.to_string());
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(bad)?;
+    let head = std::str::from_utf8(&raw[..split]).map_err(|_| bad())?;
+    let body = &raw[split + 4..];
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|l| l.split(' ').nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(bad)?;
+    if status != 200 {
+        return Err(FetchError::Status {
+            url: url.to_string(),
+            status,
+        });
+    }
+    let mut body_len = body.len();
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or_else(bad)?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") && value != "identity" {
+            return Err(bad());
+        }
+        if name.eq_ignore_ascii_case("content-length") {
+            body_len = value.parse().map_err(|_| bad())?;
+        }
+    }
+    let body = body.get(..body_len).ok_or_else(bad)?;
+    String::from_utf8(body.to_vec()).map_err(|_| bad())
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        let (host, base_path) = self.split_url()?;
+        let path = format!("{}/{}/day/{}/input", base_path, year, day);
+        let url = format!("http://{}{}", host, path);
+        let mut stream = TcpStream::connect(host)?;
+        let mut req = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: advent2021\r\nConnection: close\r\n",
+            path, host
+        );
+        if let Some(session) = &self.session {
+            req.push_str(&format!("Cookie: session={}\r\n", session));
+        }
+        req.push_str("\r\n");
+        stream.write_all(req.as_bytes())?;
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        parse_response(&url, &raw)
+    }
+    fn source(&self) -> String {
+        self.base_url.clone()
+    }
+}
+
+/// 64-bit FNV-1a, used to detect cache entries that were modified or
+/// truncated after being written.
+pub fn checksum(s: &str) -> String {
+    let hash = s.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CacheMeta {
+    pub year: u16,
+    pub day: u8,
+    pub checksum: String,
+    pub bytes: usize,
+    pub source: String,
+    pub fetched_at: u64,
+}
+
+/// Keeps fetched inputs under `<dir>/<year>/[<profile>/]dayN.txt`, with a
+/// `dayN.meta.toml` next to each recording where it came from and its
+/// checksum. Entries whose checksum no longer matches are fetched again.
+pub struct InputCache {
+    dir: PathBuf,
+    year: u16,
+    profile: Option<String>,
+    fetcher: Box<dyn Fetcher>,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>, fetcher: Box<dyn Fetcher>) -> Self {
+        Self {
+            dir: dir.into(),
+            year: DEFAULT_YEAR,
+            profile: None,
+            fetcher,
+        }
+    }
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = year;
+        self
+    }
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+    fn entry_dir(&self) -> PathBuf {
+        let d = self.dir.join(self.year.to_string());
+        match &self.profile {
+            Some(p) => d.join(p),
+            None => d,
+        }
+    }
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.entry_dir().join(format!("day{}.txt", day))
+    }
+    pub fn meta_path(&self, day: u8) -> PathBuf {
+        self.entry_dir().join(format!("day{}.meta.toml", day))
+    }
+    pub fn meta(&self, day: u8) -> Option<CacheMeta> {
+        let s = fs::read_to_string(self.meta_path(day)).ok()?;
+        toml::from_str(&s).ok()
+    }
+    fn cached(&self, day: u8) -> Option<String> {
+        let meta = self.meta(day)?;
+        let s = fs::read_to_string(self.input_path(day)).ok()?;
+        if checksum(&s) == meta.checksum {
+            Some(s)
+        } else {
+            None
+        }
+    }
+    fn store(&self, day: u8, input: &str) -> Result<(), InputError> {
+        let io_err = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| InputError::Io { path, source }
+        };
+        let dir = self.entry_dir();
+        fs::create_dir_all(&dir).map_err(io_err(&dir))?;
+        let path = self.input_path(day);
+        fs::write(&path, input).map_err(io_err(&path))?;
+        let meta = CacheMeta {
+            year: self.year,
+            day,
+            checksum: checksum(input),
+            bytes: input.len(),
+            source: self.fetcher.source(),
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+        let meta_path = self.meta_path(day);
+        let s = toml::to_string(&meta).map_err(|source| InputError::Meta {
+            path: meta_path.clone(),
+            source,
+        })?;
+        fs::write(&meta_path, s).map_err(io_err(&meta_path))
+    }
+}
+
+impl InputProvider for InputCache {
+    fn input(&self, day: u8) -> Result<String, InputError> {
+        if let Some(s) = self.cached(day) {
+            return Ok(s);
+        }
+        let s = self
+            .fetcher
+            .fetch(self.year, day)
+            .map_err(|source| InputError::Fetch { day, source })?;
+        self.store(day, &s)?;
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    struct Counting(Arc<AtomicUsize>);
+
+    impl Fetcher for Counting {
+        fn fetch(&self, _year: u16, day: u8) -> Result<String, FetchError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("input for {}\n", day))
+        }
+        fn source(&self) -> String {
+            "counting".to_string()
+        }
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!("cbf29ce484222325", checksum(""));
+        assert_eq!("af63dc4c8601ec8c", checksum("a"));
+    }
+
+    #[test]
+    fn test_cache_fetches_once() -> anyhow::Result<()> {
+        let d = scratch_dir("cache-once");
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = InputCache::new(&*d, Box::new(Counting(calls.clone())));
+        assert_eq!("input for 3\n", cache.input(3)?);
+        assert_eq!("input for 3\n", cache.input(3)?);
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+        let meta = cache.meta(3).expect("metadata written");
+        assert_eq!(checksum("input for 3\n"), meta.checksum);
+        assert_eq!("counting", meta.source);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_refetches_corrupt_entry() -> anyhow::Result<()> {
+        let d = scratch_dir("cache-corrupt");
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = InputCache::new(&*d, Box::new(Counting(calls.clone())))
+            .with_profile(Some("sam".to_string()));
+        cache.input(1)?;
+        fs::write(cache.input_path(1), "truncated")?;
+        assert_eq!("input for 1\n", cache.input(1)?);
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixture_fetcher() -> anyhow::Result<()> {
+        let d = scratch_dir("fixture");
+        fs::create_dir_all(d.join("2021"))?;
+        fs::write(d.join("2021").join("day2.txt"), "nested")?;
+        fs::write(d.join("day5.txt"), "flat")?;
+        let f = FixtureFetcher::new(&*d);
+        assert_eq!("nested", f.fetch(2021, 2)?);
+        assert_eq!("flat", f.fetch(2021, 5)?);
+        assert!(f.fetch(2021, 6).is_err());
+        Ok(())
+    }
+
+    fn serve_once(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut req = Vec::new();
+            let mut buf = [0; 512];
+            while !req.ends_with(b"\r\n\r\n") {
+                let n = conn.read(&mut buf).unwrap();
+                req.extend_from_slice(&buf[..n]);
+            }
+            conn.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(req).unwrap()
+        });
+        (format!("http://{}/aoc", addr), handle)
+    }
+
+    #[test]
+    fn test_http_fetcher() -> anyhow::Result<()> {
+        let (url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1\n2\n3\n");
+        let f = HttpFetcher::new(&url, Some("abc".to_string()));
+        assert_eq!("1\n2\n3\n", f.fetch(2021, 7)?);
+        let req = server.join().unwrap();
+        assert!(
+            req.starts_with("GET /aoc/2021/day/7/input HTTP/1.1\r\n"),
+            "{}",
+            req
+        );
+        assert!(req.contains("Cookie: session=abc\r\n"), "{}", req);
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_fetcher_status() {
+        let (url, server) = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        let f = HttpFetcher::new(&url, None);
+        match f.fetch(2021, 1) {
+            Err(FetchError::Status { status, .. }) => assert_eq!(404, status),
+            other => panic!("expected status error, got {:?}", other),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_spec_rejects_https() {
+        let e = fetcher_from_spec("https://adventofcode.com", None).err();
+        assert!(
+            matches!(e, Some(FetchError::HttpsUnsupported(_))),
+            "{:?}",
+            e
+        );
+        assert!(fetcher_from_spec("http://localhost:8000", None).is_ok());
+    }
+
+    #[test]
+    fn test_http_fetcher_rejects_https() {
+        let f = HttpFetcher::new("https://adventofcode.com", None);
+        assert!(matches!(f.fetch(2021, 1), Err(FetchError::BadUrl(_))));
+    }
+}
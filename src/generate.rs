@@ -0,0 +1,326 @@
+use anyhow::{anyhow, Result};
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+
+use std::collections::HashSet;
+use std::io::Write;
+
+/// Attempts at drawing diagnostic readings whose ratings can be found.
+const RATING_ATTEMPTS: usize = 100;
+
+/// Sizes for the generated inputs. Anything left unset gets a value close to
+/// the real puzzle input's.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    /// Number of depths, commands, readings, boards, vent lines, fish or
+    /// crabs.
+    pub count: Option<usize>,
+    /// Largest bingo number, vent coordinate or crab position.
+    pub max: Option<u32>,
+    /// Bits in each diagnostic reading.
+    pub bits: Option<u32>,
+    /// Rows and columns in each bingo board.
+    pub board_size: Option<usize>,
+    /// Only generate horizontal and vertical vent lines.
+    pub no_diagonals: bool,
+}
+
+/// The random number generator used for `seed`, which gives the same
+/// numbers on every platform and release.
+pub fn rng(seed: u64) -> Pcg64 {
+    Pcg64::seed_from_u64(seed)
+}
+
+/// Whether there is a generator for `day`.
+pub fn supports(day: u8) -> bool {
+    (1..=7).contains(&day)
+}
+
+/// Writes an input for `day` generated from `seed`.
+pub fn generate(day: u8, options: &Options, seed: u64, out: &mut dyn Write) -> Result<()> {
+    if options.count == Some(0) {
+        return Err(anyhow!("cannot generate an input with nothing in it"));
+    }
+    let rng = &mut rng(seed);
+    let count = |default| options.count.unwrap_or(default);
+    match day {
+        1 => depths(rng, count(2000), out),
+        2 => commands(rng, count(1000), out),
+        3 => diagnostics(rng, count(1000), options.bits.unwrap_or(12), out),
+        4 => bingo(
+            rng,
+            count(100),
+            options.board_size.unwrap_or(5),
+            options.max.unwrap_or(99),
+            out,
+        ),
+        5 => vents(
+            rng,
+            count(500),
+            options.max.unwrap_or(989),
+            !options.no_diagonals,
+            out,
+        ),
+        6 => fish(rng, count(300), out),
+        7 => crabs(rng, count(1000), options.max.unwrap_or(1999), out),
+        _ => Err(anyhow!("no generator for day {}", day)),
+    }
+}
+
+fn comma_list<T: ToString>(
+    out: &mut dyn Write,
+    items: impl Iterator<Item = T>,
+) -> std::io::Result<()> {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "{}", item.to_string())?;
+    }
+    writeln!(out)
+}
+
+/// Sonar depths, mostly getting deeper.
+pub fn depths(rng: &mut impl Rng, count: usize, out: &mut dyn Write) -> Result<()> {
+    let mut depth: u64 = rng.gen_range(100..200);
+    for _ in 0..count {
+        writeln!(out, "{}", depth)?;
+        depth = depth.saturating_add_signed(rng.gen_range(-10..=20));
+    }
+    Ok(())
+}
+
+/// Submarine commands moving up to 9 at a time.
+pub fn commands(rng: &mut impl Rng, count: usize, out: &mut dyn Write) -> Result<()> {
+    const DIRECTIONS: [&str; 3] = ["forward", "down", "up"];
+    for _ in 0..count {
+        let dir = DIRECTIONS.choose(rng).copied().unwrap_or("forward");
+        writeln!(out, "{} {}", dir, rng.gen_range(1..=9))?;
+    }
+    Ok(())
+}
+
+/// Whether the oxygen and CO2 filters each end at a single reading, which
+/// needs both bit values present wherever the CO2 filter looks.
+fn ratings_resolve(readings: &[u64], bits: u32) -> bool {
+    [true, false].iter().all(|&most_common| {
+        let mut left = readings.to_vec();
+        for pos in (0..bits).rev() {
+            if left.len() <= 1 {
+                break;
+            }
+            let ones = left.iter().filter(|r| (*r >> pos) & 1 == 1).count();
+            let zeros = left.len() - ones;
+            let keep = if most_common {
+                ones >= zeros
+            } else {
+                ones < zeros
+            };
+            left.retain(|r| ((r >> pos) & 1 == 1) == keep);
+        }
+        left.len() == 1
+    })
+}
+
+fn distinct_readings(rng: &mut impl Rng, count: usize, bits: u32) -> Vec<u64> {
+    let space = 1u64 << bits;
+    if count as u64 > space / 2 {
+        let mut all: Vec<u64> = (0..space).collect();
+        all.partial_shuffle(rng, count);
+        all.truncate(count);
+        return all;
+    }
+    let mut seen = HashSet::with_capacity(count);
+    while seen.len() < count {
+        seen.insert(rng.gen_range(0..space));
+    }
+    let mut readings: Vec<u64> = seen.into_iter().collect();
+    readings.sort_unstable();
+    readings.shuffle(rng);
+    readings
+}
+
+/// Distinct diagnostic readings `bits` wide, drawn until both life support
+/// ratings can be found.
+pub fn diagnostics(rng: &mut impl Rng, count: usize, bits: u32, out: &mut dyn Write) -> Result<()> {
+    if !(1..=32).contains(&bits) {
+        return Err(anyhow!("readings must be 1 to 32 bits, not {}", bits));
+    }
+    if count == 0 || count as u64 > 1 << bits {
+        return Err(anyhow!(
+            "cannot make {} distinct {}-bit readings",
+            count,
+            bits
+        ));
+    }
+    let readings = (0..RATING_ATTEMPTS)
+        .map(|_| distinct_readings(rng, count, bits))
+        .find(|r| ratings_resolve(r, bits))
+        .ok_or_else(|| {
+            anyhow!(
+                "no ratable set of {} {}-bit readings found; try another seed",
+                count,
+                bits
+            )
+        })?;
+    for r in readings {
+        writeln!(out, "{:0width$b}", r, width = bits as usize)?;
+    }
+    Ok(())
+}
+
+/// A bingo game drawing every number up to `max`, so every board wins, and
+/// `boards` boards of `size` by `size` distinct numbers.
+pub fn bingo(
+    rng: &mut impl Rng,
+    boards: usize,
+    size: usize,
+    max: u32,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let numbers = max as usize + 1;
+    let cells = size.saturating_mul(size);
+    if size == 0 || cells > numbers {
+        return Err(anyhow!(
+            "cannot fill a {}x{} board from numbers up to {}",
+            size,
+            size,
+            max
+        ));
+    }
+    let mut balls: Vec<u32> = (0..=max).collect();
+    balls.shuffle(rng);
+    comma_list(out, balls.iter())?;
+    let width = max.to_string().len();
+    for _ in 0..boards {
+        writeln!(out)?;
+        let board = index::sample(rng, numbers, cells).into_vec();
+        for row in board.chunks(size) {
+            let row: Vec<String> = row
+                .iter()
+                .map(|n| format!("{:>width$}", n, width = width))
+                .collect();
+            writeln!(out, "{}", row.join(" "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Horizontal, vertical and, unless `diagonals` is false, 45 degree vent
+/// lines with both ends between 0 and `max`.
+pub fn vents(
+    rng: &mut impl Rng,
+    count: usize,
+    max: u32,
+    diagonals: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let kinds = if diagonals { 3 } else { 2 };
+    for _ in 0..count {
+        let (x1, y1) = (rng.gen_range(0..=max), rng.gen_range(0..=max));
+        let (x2, y2) = match rng.gen_range(0..kinds) {
+            0 => (rng.gen_range(0..=max), y1),
+            1 => (x1, rng.gen_range(0..=max)),
+            _ => {
+                let (right, down) = (rng.gen::<bool>(), rng.gen::<bool>());
+                let room_x = if right { max - x1 } else { x1 };
+                let room_y = if down { max - y1 } else { y1 };
+                let len = rng.gen_range(0..=room_x.min(room_y));
+                let x2 = if right { x1 + len } else { x1 - len };
+                let y2 = if down { y1 + len } else { y1 - len };
+                (x2, y2)
+            }
+        };
+        writeln!(out, "{},{} -> {},{}", x1, y1, x2, y2)?;
+    }
+    Ok(())
+}
+
+/// Lanternfish timers between 1 and 5, as in the puzzle input.
+pub fn fish(rng: &mut impl Rng, count: usize, out: &mut dyn Write) -> Result<()> {
+    Ok(comma_list(out, (0..count).map(|_| rng.gen_range(1..=5u8)))?)
+}
+
+/// Crab positions between 0 and `max`.
+pub fn crabs(rng: &mut impl Rng, count: usize, max: u32, out: &mut dyn Write) -> Result<()> {
+    Ok(comma_list(out, (0..count).map(|_| rng.gen_range(0..=max)))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generated(day: u8, options: &Options, seed: u64) -> Result<String> {
+        let mut out = Vec::new();
+        generate(day, options, seed, &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_same_seed_same_input() -> Result<()> {
+        let options = Options::default();
+        for day in 1..=7 {
+            assert_eq!(generated(day, &options, 7)?, generated(day, &options, 7)?);
+            assert_ne!(generated(day, &options, 7)?, generated(day, &options, 8)?);
+        }
+        assert!(generated(8, &options, 7).is_err());
+        assert!((1..=7).all(supports) && !supports(8));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sizes() -> Result<()> {
+        let options = Options {
+            count: Some(20),
+            max: Some(30),
+            bits: Some(5),
+            board_size: Some(3),
+            no_diagonals: true,
+        };
+        assert_eq!(20, generated(1, &options, 1)?.lines().count());
+        let readings = generated(3, &options, 1)?;
+        assert_eq!(20, readings.lines().count());
+        assert!(readings.lines().all(|l| l.len() == 5));
+        let game = generated(4, &options, 1)?;
+        assert_eq!(1 + 20 * 4, game.lines().count());
+        assert_eq!(3, game.lines().nth(2).unwrap().split_whitespace().count());
+        for line in generated(5, &options, 1)?.lines() {
+            let n: Vec<u32> = line
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().unwrap())
+                .collect();
+            assert!(n.iter().all(|&c| c <= 30), "{}", line);
+            assert!(n[0] == n[2] || n[1] == n[3], "{}", line);
+        }
+        assert_eq!(20, generated(7, &options, 1)?.trim().split(',').count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_impossible_sizes() {
+        let too_many = Options {
+            count: Some(9),
+            bits: Some(3),
+            ..Default::default()
+        };
+        assert!(generated(3, &too_many, 0).is_err());
+        let too_few_numbers = Options {
+            max: Some(10),
+            ..Default::default()
+        };
+        assert!(generated(4, &too_few_numbers, 0).is_err());
+        let empty = Options {
+            count: Some(0),
+            ..Default::default()
+        };
+        assert!((1..=7).all(|day| generated(day, &empty, 0).is_err()));
+    }
+
+    #[test]
+    fn test_ratings_resolve() {
+        assert!(ratings_resolve(&[0b10, 0b01, 0b11], 2));
+        assert!(!ratings_resolve(&[0b10, 0b11], 2));
+    }
+}
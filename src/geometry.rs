@@ -0,0 +1,336 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on a plane with `y` growing downwards, as in puzzle maps and
+/// depths below the surface.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The offset between two [`Point2`]s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Self = Self { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+    pub fn checked_add(self, v: Vec2) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add(v.x)?,
+            self.y.checked_add(v.y)?,
+        ))
+    }
+    /// The offset from `other` to `self`.
+    pub fn checked_sub(self, other: Self) -> Option<Vec2> {
+        Some(Vec2::new(
+            self.x.checked_sub(other.x)?,
+            self.y.checked_sub(other.y)?,
+        ))
+    }
+    pub fn manhattan(self, other: Self) -> Option<u64> {
+        other.checked_sub(self)?.manhattan()
+    }
+    pub fn chebyshev(self, other: Self) -> Option<u64> {
+        Some(other.checked_sub(self)?.chebyshev())
+    }
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add(other.x)?,
+            self.y.checked_add(other.y)?,
+        ))
+    }
+    pub fn checked_mul(self, n: i64) -> Option<Self> {
+        Some(Self::new(self.x.checked_mul(n)?, self.y.checked_mul(n)?))
+    }
+    /// A vector with each component replaced by its sign, which steps one
+    /// cell along horizontal, vertical and diagonal lines.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+    pub fn manhattan(self) -> Option<u64> {
+        self.x.unsigned_abs().checked_add(self.y.unsigned_abs())
+    }
+    pub fn chebyshev(self) -> u64 {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl Add<Vec2> for Point2 {
+    type Output = Point2;
+    fn add(self, v: Vec2) -> Point2 {
+        Point2::new(self.x + v.x, self.y + v.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point2 {
+    fn add_assign(&mut self, v: Vec2) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vec2> for Point2 {
+    type Output = Point2;
+    fn sub(self, v: Vec2) -> Point2 {
+        Point2::new(self.x - v.x, self.y - v.y)
+    }
+}
+
+impl SubAssign<Vec2> for Point2 {
+    fn sub_assign(&mut self, v: Vec2) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Vec2;
+    fn sub(self, other: Point2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, n: i64) -> Vec2 {
+        Vec2::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+/// The four orthogonal directions, with up towards smaller `y`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn unit(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Down => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+        }
+    }
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+}
+
+/// The eight compass directions, with north towards smaller `y`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    pub fn unit(self) -> Vec2 {
+        match self {
+            Self::N => Vec2::new(0, -1),
+            Self::NE => Vec2::new(1, -1),
+            Self::E => Vec2::new(1, 0),
+            Self::SE => Vec2::new(1, 1),
+            Self::S => Vec2::new(0, 1),
+            Self::SW => Vec2::new(-1, 1),
+            Self::W => Vec2::new(-1, 0),
+            Self::NW => Vec2::new(-1, -1),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Self {
+        match d {
+            Dir4::Up => Self::N,
+            Dir4::Right => Self::E,
+            Dir4::Down => Self::S,
+            Dir4::Left => Self::W,
+        }
+    }
+}
+
+/// The smallest axis-aligned rectangle containing a set of points, with both
+/// corners inclusive.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl BoundingBox {
+    pub fn new(p: Point2) -> Self {
+        Self { min: p, max: p }
+    }
+    /// The box around `points`, or `None` if there are none.
+    pub fn around(points: impl IntoIterator<Item = Point2>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first), Self::including))
+    }
+    /// This box grown to contain `p`.
+    pub fn including(self, p: Point2) -> Self {
+        Self {
+            min: Point2::new(self.min.x.min(p.x), self.min.y.min(p.y)),
+            max: Point2::new(self.max.x.max(p.x), self.max.y.max(p.y)),
+        }
+    }
+    pub fn contains(&self, p: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+    /// The number of columns covered, saturating for boxes spanning every
+    /// `i64`.
+    pub fn width(&self) -> u64 {
+        self.max.x.abs_diff(self.min.x).saturating_add(1)
+    }
+    /// The number of rows covered, saturating like [`width`](Self::width).
+    pub fn height(&self) -> u64 {
+        self.max.y.abs_diff(self.min.y).saturating_add(1)
+    }
+    pub fn area(&self) -> Option<u64> {
+        self.width().checked_mul(self.height())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point2::new(3, -2);
+        let v = Vec2::new(-5, 4);
+        assert_eq!(Point2::new(-2, 2), p + v);
+        assert_eq!(Point2::new(8, -6), p - v);
+        assert_eq!(v, (p + v) - p);
+        assert_eq!(Vec2::new(-15, 12), v * 3);
+        assert_eq!(Vec2::new(5, -4), -v);
+        assert_eq!(Vec2::new(-1, 1), v.signum());
+        let mut q = p;
+        q += v;
+        q -= v;
+        assert_eq!(p, q);
+        assert_eq!(None, Point2::new(i64::MAX, 0).checked_add(Vec2::new(1, 0)));
+        assert_eq!(None, v.checked_mul(i64::MAX));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point2::new(1, 1);
+        let b = Point2::new(-2, 5);
+        assert_eq!(Some(7), a.manhattan(b));
+        assert_eq!(Some(4), a.chebyshev(b));
+        assert_eq!(
+            None,
+            Point2::new(i64::MIN, 0).manhattan(Point2::new(i64::MAX, 0))
+        );
+    }
+
+    #[test]
+    fn test_directions() {
+        let sum = Dir4::ALL.iter().fold(Vec2::ZERO, |acc, d| acc + d.unit());
+        assert_eq!(Vec2::ZERO, sum);
+        assert_eq!(Dir4::Left, Dir4::Up.turn_left());
+        assert_eq!(Dir4::Up, Dir4::Left.turn_right());
+        for d in Dir4::ALL.iter() {
+            assert_eq!(d.unit(), Dir8::from(*d).unit());
+        }
+        assert!(Dir8::ALL.iter().all(|d| d.unit().chebyshev() == 1));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(None, BoundingBox::around(Vec::new()));
+        let b = BoundingBox::around(vec![
+            Point2::new(2, -1),
+            Point2::new(-3, 4),
+            Point2::new(0, 0),
+        ])
+        .unwrap();
+        assert_eq!(Point2::new(-3, -1), b.min);
+        assert_eq!(Point2::new(2, 4), b.max);
+        assert_eq!((6, 6), (b.width(), b.height()));
+        assert_eq!(Some(36), b.area());
+        assert!(b.contains(Point2::new(-3, 4)));
+        assert!(!b.contains(Point2::new(3, 0)));
+        let huge = BoundingBox::new(Point2::new(i64::MIN, i64::MIN))
+            .including(Point2::new(i64::MAX, i64::MAX));
+        assert_eq!(None, huge.area());
+    }
+}
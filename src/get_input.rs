@@ -1,7 +1,23 @@
-use anyhow::Result;
+use serde::Deserialize;
+use thiserror::Error;
 
+use crate::error::Result;
+use crate::fetch::{fetcher_from_spec, FetchError, InputCache};
+
+use std::env;
+use std::fmt;
 use std::fs;
-use std::io::Read;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_YEAR: u16 = 2021;
+pub const INPUT_DIR_VAR: &str = "ADVENT_INPUT_DIR";
+pub const PROFILE_VAR: &str = "ADVENT_PROFILE";
+pub const CONFIG_VAR: &str = "ADVENT_CONFIG";
+pub const CACHE_DIR_VAR: &str = "ADVENT_CACHE_DIR";
+pub const FETCH_VAR: &str = "ADVENT_FETCH";
+pub const SESSION_VAR: &str = "ADVENT_SESSION";
+pub const CONFIG_FILE: &str = "advent2021.toml";
 
 pub fn get_input(pth: &str) -> Result<String> {
     let mut s = String::new();
@@ -9,3 +25,408 @@ pub fn get_input(pth: &str) -> Result<String> {
     f.read_to_string(&mut s)?;
     Ok(s)
 }
+
+/// Reads the input for `day` using the provider configured by the
+/// environment and config file.
+pub fn read_day(day: u8) -> Result<String> {
+    Ok(InputConfig::from_env()?.provider()?.input(day)?)
+}
+
+pub trait InputProvider {
+    fn input(&self, day: u8) -> Result<String, InputError>;
+}
+
+/// Uses `B` for any day `A` has no input for.
+pub struct Fallback<A, B>(pub A, pub B);
+
+impl<A: InputProvider, B: InputProvider> InputProvider for Fallback<A, B> {
+    fn input(&self, day: u8) -> Result<String, InputError> {
+        match self.0.input(day) {
+            Err(InputError::NotFound { .. }) => self.1.input(day),
+            r => r,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("no input found for day {day}; tried {}", PathList(.tried))]
+    NotFound { day: u8, tried: Vec<PathBuf> },
+    #[error("could not read {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("could not read stdin: {0}")]
+    Stdin(io::Error),
+    #[error("could not fetch input for day {day}: {source}")]
+    Fetch { day: u8, source: FetchError },
+    #[error("invalid fetch source: {0}")]
+    FetchSource(FetchError),
+    #[error("could not write cache metadata {}: {source}", .path.display())]
+    Meta {
+        path: PathBuf,
+        source: toml::ser::Error,
+    },
+    #[error("invalid config {}: {source}", .path.display())]
+    Config {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+struct PathList<'a>(&'a [PathBuf]);
+
+impl fmt::Display for PathList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, p) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", p.display())?;
+        }
+        Ok(())
+    }
+}
+
+fn read_path(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Where a single puzzle input comes from: `-` means stdin, anything else
+/// is a file path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    pub fn read(&self) -> Result<String, InputError> {
+        match self {
+            Self::Stdin => {
+                let mut s = String::new();
+                io::stdin()
+                    .read_to_string(&mut s)
+                    .map_err(InputError::Stdin)?;
+                Ok(s)
+            }
+            Self::File(path) => read_path(path),
+        }
+    }
+    /// A buffered reader over the input, for days that can solve it
+    /// without reading it all into memory.
+    pub fn open(&self) -> Result<Box<dyn BufRead>, InputError> {
+        match self {
+            Self::Stdin => Ok(Box::new(io::stdin().lock())),
+            Self::File(path) => {
+                let f = fs::File::open(path).map_err(|source| InputError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                Ok(Box::new(BufReader::new(f)))
+            }
+        }
+    }
+}
+
+impl From<&str> for InputSource {
+    fn from(s: &str) -> Self {
+        if s == "-" {
+            Self::Stdin
+        } else {
+            Self::File(PathBuf::from(s))
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct InputConfig {
+    pub input_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub year: Option<u16>,
+    pub cache_dir: Option<PathBuf>,
+    /// Fetcher spec: an `http://` base url or a fixture directory.
+    pub fetch: Option<String>,
+    pub session: Option<String>,
+}
+
+impl InputConfig {
+    pub fn load(path: &Path) -> Result<Self, InputError> {
+        let s = read_path(path)?;
+        toml::from_str(&s).map_err(|source| InputError::Config {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+    /// Loads the file named by `ADVENT_CONFIG`, or `advent2021.toml` in the
+    /// working directory if it exists.
+    pub fn discover() -> Result<Self, InputError> {
+        match env::var_os(CONFIG_VAR) {
+            Some(p) => Self::load(Path::new(&p)),
+            None if Path::new(CONFIG_FILE).is_file() => Self::load(Path::new(CONFIG_FILE)),
+            None => Ok(Self::default()),
+        }
+    }
+    /// The discovered config file with any `ADVENT_*` variables applied
+    /// on top.
+    pub fn from_env() -> Result<Self, InputError> {
+        let path_var = |k| env::var_os(k).map(PathBuf::from);
+        let env = Self {
+            input_dir: path_var(INPUT_DIR_VAR),
+            profile: env::var(PROFILE_VAR).ok(),
+            year: None,
+            cache_dir: path_var(CACHE_DIR_VAR),
+            fetch: env::var(FETCH_VAR).ok(),
+            session: env::var(SESSION_VAR).ok(),
+        };
+        Ok(Self::discover()?.overlay(env))
+    }
+    /// Returns `self` with every setting present in `other` replaced.
+    pub fn overlay(self, other: Self) -> Self {
+        Self {
+            input_dir: other.input_dir.or(self.input_dir),
+            profile: other.profile.or(self.profile),
+            year: other.year.or(self.year),
+            cache_dir: other.cache_dir.or(self.cache_dir),
+            fetch: other.fetch.or(self.fetch),
+            session: other.session.or(self.session),
+        }
+    }
+    pub fn resolver(&self) -> InputResolver {
+        InputResolver::default().with_config(self.clone())
+    }
+    fn cache(&self, resolver: &InputResolver) -> Result<Option<InputCache>, InputError> {
+        match (&self.cache_dir, &self.fetch) {
+            (Some(dir), Some(spec)) => {
+                let fetcher = fetcher_from_spec(spec, self.session.clone())
+                    .map_err(InputError::FetchSource)?;
+                Ok(Some(
+                    InputCache::new(dir, fetcher)
+                        .with_year(resolver.year())
+                        .with_profile(resolver.profile().map(String::from)),
+                ))
+            }
+            _ => Ok(None),
+        }
+    }
+    /// The resolver, falling back to a fetching cache when both `cache_dir`
+    /// and `fetch` are set.
+    pub fn provider(&self) -> Result<Box<dyn InputProvider>, InputError> {
+        let resolver = self.resolver();
+        Ok(match self.cache(&resolver)? {
+            Some(cache) => Box::new(Fallback(resolver, cache)),
+            None => Box::new(resolver),
+        })
+    }
+    /// The file the provider reads `day`'s input from, fetching it into the
+    /// cache first if that is where it would come from.
+    pub fn input_path(&self, day: u8) -> Result<PathBuf, InputError> {
+        let resolver = self.resolver();
+        match (resolver.resolve(day), self.cache(&resolver)?) {
+            (Err(InputError::NotFound { .. }), Some(cache)) => {
+                cache.input(day)?;
+                Ok(cache.input_path(day))
+            }
+            (found, _) => found,
+        }
+    }
+}
+
+/// Finds puzzle inputs by year, day and (optionally) profile under an input
+/// directory.
+///
+/// With a profile, inputs are looked up at `<dir>/<year>/<profile>/dayN.txt`
+/// then `<dir>/<profile>/dayN.txt`; profiles never fall back to shared
+/// inputs. Without one, `<dir>/<year>/dayN.txt` then `<dir>/dayN.txt`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InputResolver {
+    dir: PathBuf,
+    year: u16,
+    profile: Option<String>,
+}
+
+impl Default for InputResolver {
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+impl InputResolver {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            year: DEFAULT_YEAR,
+            profile: None,
+        }
+    }
+    /// Builds a resolver from the config file, with `ADVENT_INPUT_DIR` and
+    /// `ADVENT_PROFILE` taking precedence over it.
+    pub fn from_env() -> Result<Self, InputError> {
+        Ok(InputConfig::from_env()?.resolver())
+    }
+    pub fn with_config(mut self, config: InputConfig) -> Self {
+        if let Some(dir) = config.input_dir {
+            self.dir = dir;
+        }
+        if let Some(year) = config.year {
+            self.year = year;
+        }
+        if config.profile.is_some() {
+            self.profile = config.profile;
+        }
+        self
+    }
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = year;
+        self
+    }
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+    pub fn candidates(&self, day: u8) -> Vec<PathBuf> {
+        let file = format!("day{}.txt", day);
+        let year = self.year.to_string();
+        match &self.profile {
+            Some(p) => vec![
+                self.dir.join(&year).join(p).join(&file),
+                self.dir.join(p).join(&file),
+            ],
+            None => vec![self.dir.join(&year).join(&file), self.dir.join(&file)],
+        }
+    }
+    pub fn resolve(&self, day: u8) -> Result<PathBuf, InputError> {
+        let tried = self.candidates(day);
+        match tried.iter().find(|p| p.is_file()) {
+            Some(p) => Ok(p.clone()),
+            None => Err(InputError::NotFound { day, tried }),
+        }
+    }
+    pub fn read(&self, day: u8) -> Result<String, InputError> {
+        read_path(&self.resolve(day)?)
+    }
+}
+
+impl InputProvider for InputResolver {
+    fn input(&self, day: u8) -> Result<String, InputError> {
+        self.read(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+
+    #[test]
+    fn test_resolve_prefers_year_dir() -> Result<()> {
+        let d = scratch_dir("year");
+        fs::create_dir_all(d.join("2021"))?;
+        fs::write(d.join("2021").join("day3.txt"), "year")?;
+        fs::write(d.join("day3.txt"), "flat")?;
+        fs::write(d.join("day4.txt"), "flat")?;
+        let r = InputResolver::new(&*d);
+        assert_eq!("year", r.read(3)?);
+        assert_eq!("flat", r.read(4)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_does_not_fall_back() -> Result<()> {
+        let d = scratch_dir("profile");
+        fs::create_dir_all(d.join("2021").join("sam"))?;
+        fs::write(d.join("2021").join("sam").join("day1.txt"), "sam's")?;
+        fs::write(d.join("day2.txt"), "shared")?;
+        let r = InputResolver::new(&*d).with_profile("sam");
+        assert_eq!("sam's", r.read(1)?);
+        match r.read(2) {
+            Err(InputError::NotFound { day, tried }) => {
+                assert_eq!(2, day);
+                assert_eq!(tried, r.candidates(2));
+            }
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_found_message_names_paths() {
+        let r = InputResolver::new("nowhere");
+        let msg = r.read(9).unwrap_err().to_string();
+        assert!(msg.contains("day 9"), "{}", msg);
+        assert!(msg.contains(&Path::new("nowhere").join("day9.txt").display().to_string()));
+    }
+
+    #[test]
+    fn test_config() {
+        let c: InputConfig = toml::from_str("input_dir = \"inputs\"\nprofile = \"sam\"\n").unwrap();
+        let r = InputResolver::default().with_config(c);
+        assert_eq!(Path::new("inputs"), r.dir());
+        assert_eq!(Some("sam"), r.profile());
+        assert_eq!(DEFAULT_YEAR, r.year());
+    }
+
+    #[test]
+    fn test_config_overlay() {
+        let file = InputConfig {
+            input_dir: Some(PathBuf::from("inputs")),
+            profile: Some("sam".to_string()),
+            ..Default::default()
+        };
+        let flags = InputConfig {
+            profile: Some("alex".to_string()),
+            ..Default::default()
+        };
+        let c = file.overlay(flags);
+        assert_eq!(Some(PathBuf::from("inputs")), c.input_dir);
+        assert_eq!(Some("alex".to_string()), c.profile);
+    }
+
+    #[test]
+    fn test_provider_falls_back_to_cache() -> Result<()> {
+        let d = scratch_dir("fallback");
+        fs::create_dir_all(d.join("fixtures"))?;
+        fs::write(d.join("fixtures").join("day6.txt"), "3,4,3,1,2\n")?;
+        let config = InputConfig {
+            input_dir: Some(d.join("inputs")),
+            cache_dir: Some(d.join("cache")),
+            fetch: Some(d.join("fixtures").display().to_string()),
+            ..Default::default()
+        };
+        assert_eq!("3,4,3,1,2\n", config.provider()?.input(6)?);
+        assert!(d.join("cache").join("2021").join("day6.txt").is_file());
+        assert_eq!(
+            d.join("cache").join("2021").join("day6.txt"),
+            config.input_path(6)?
+        );
+        assert!(matches!(
+            config.provider()?.input(7),
+            Err(InputError::Fetch { day: 7, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_source_from_str() {
+        assert_eq!(InputSource::Stdin, InputSource::from("-"));
+        assert_eq!(
+            InputSource::File(PathBuf::from("day1.txt")),
+            InputSource::from("day1.txt")
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row, addressed by `(x, y)` with `(0, 0)`
+/// at the top left.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Result<Self> {
+        let len = width
+            .checked_mul(height)
+            .ok_or(Error::Overflow("grid size"))?;
+        Ok(Self {
+            cells: vec![fill; len],
+            width,
+            height,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid `width` cells wide from cells given row by row.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Result<Self> {
+        if width == 0 || !cells.len().is_multiple_of(width) {
+            return Err(Error::invalid(format!(
+                "{} cells do not make rows of {}",
+                cells.len(),
+                width
+            )));
+        }
+        Ok(Self {
+            height: cells.len() / width,
+            cells,
+            width,
+        })
+    }
+    /// A grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(y) = rows.iter().position(|r| r.len() != width) {
+            return Err(Error::invalid(format!(
+                "row {} has {} cells, expected {}",
+                y,
+                rows[y].len(),
+                width
+            )));
+        }
+        Self::from_vec(width, rows.into_iter().flatten().collect())
+    }
+    /// Parses a grid with one cell per character, such as a height map.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self> {
+        let mut rows = Vec::new();
+        for line in input.lines().filter(|l| !l.is_empty()) {
+            let mut row = Vec::new();
+            for (ix, c) in line.char_indices() {
+                let v = cell(c)
+                    .ok_or_else(|| Error::parse(input, &line[ix..], format!("bad cell {:?}", c)))?;
+                row.push(v);
+            }
+            if rows.first().is_some_and(|r: &Vec<T>| r.len() != row.len()) {
+                return Err(Error::parse(input, line, "rows have different lengths"));
+            }
+            rows.push(row);
+        }
+        Self::from_rows(rows)
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if self.contains(x, y) {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index(x, y).map(|ix| &self.cells[ix])
+    }
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index(x, y).map(move |ix| &mut self.cells[ix])
+    }
+    /// Every cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+    /// Every cell with its position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(ix, v)| ((ix % width, ix / width), v))
+    }
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        self.rows().nth(y)
+    }
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let skip = if x < self.width { x } else { self.cells.len() };
+        self.cells.iter().skip(skip).step_by(self.width.max(1))
+    }
+    /// The cells from `(x, y)` stepping by `(dx, dy)` until leaving the grid.
+    pub fn ray(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            next: Some((x, y)).filter(|&(x, y)| self.contains(x, y)),
+            step: (dx, dy),
+        }
+    }
+    /// The diagonal from the top left corner.
+    pub fn diagonal(&self) -> Ray<'_, T> {
+        self.ray((0, 0), (1, 1))
+    }
+    /// The diagonal from the top right corner.
+    pub fn anti_diagonal(&self) -> Ray<'_, T> {
+        self.ray((self.width.saturating_sub(1), 0), (-1, 1))
+    }
+    fn offsets<'a>(
+        &'a self,
+        (x, y): (usize, usize),
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&d| step((x, y), d))
+            .filter(move |&(x, y)| self.contains(x, y))
+    }
+    /// The positions above, left, right and below `(x, y)` that are in the
+    /// grid.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_4)
+    }
+    /// The positions around `(x, y)`, including diagonally, that are in the
+    /// grid.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_8)
+    }
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+fn step((x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+}
+
+/// Iterator over the cells along a line through a grid; see [`Grid::ray`].
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    next: Option<(usize, usize)>,
+    step: (isize, isize),
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let (x, y) = self.next?;
+        self.next = step((x, y), self.step).filter(|&(x, y)| self.grid.contains(x, y));
+        self.grid.get(x, y)
+    }
+}
+
+/// Lines up cells in columns, separated by spaces unless every cell is a
+/// single character.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown: Vec<String> = self.cells.iter().map(|c| c.to_string()).collect();
+        let w = shown.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+        for row in shown.chunks(self.width.max(1)) {
+            for (x, cell) in row.iter().enumerate() {
+                if w > 1 && x > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:>w$}", cell, w = w)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers() -> Grid<u32> {
+        Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]).unwrap()
+    }
+
+    #[test]
+    fn test_rows_columns_diagonals() {
+        let g = numbers();
+        let rows: Vec<&[u32]> = g.rows().collect();
+        assert_eq!(vec![&[1, 2, 3][..], &[4, 5, 6], &[7, 8, 9]], rows);
+        let cols: Vec<Vec<u32>> = g.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(vec![vec![1, 4, 7], vec![2, 5, 8], vec![3, 6, 9]], cols);
+        assert_eq!(vec![1, 5, 9], g.diagonal().copied().collect::<Vec<_>>());
+        assert_eq!(
+            vec![3, 5, 7],
+            g.anti_diagonal().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![4, 8],
+            g.ray((0, 1), (1, 1)).copied().collect::<Vec<_>>()
+        );
+        assert_eq!(0, g.column(3).count());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut g = numbers();
+        assert_eq!(Some(&6), g.get(2, 1));
+        assert_eq!(None, g.get(3, 1));
+        assert_eq!(None, g.get(0, 3));
+        *g.get_mut(0, 0).unwrap() = 10;
+        assert_eq!(Some(&10), g.get(0, 0));
+        assert!(g.get_mut(usize::MAX, 0).is_none());
+        assert!(Grid::<u8>::new(usize::MAX, 2, 0).is_err());
+        assert!(Grid::from_rows(vec![vec![1], vec![2, 3]]).is_err());
+        assert!(Grid::from_vec(2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let g = numbers();
+        let n4: Vec<_> = g.neighbours4((0, 0)).collect();
+        assert_eq!(vec![(1, 0), (0, 1)], n4);
+        assert_eq!(8, g.neighbours8((1, 1)).count());
+        let n8: Vec<_> = g.neighbours8((2, 2)).collect();
+        assert_eq!(vec![(1, 1), (2, 1), (1, 2)], n8);
+    }
+
+    #[test]
+    fn test_parse_and_display() -> Result<()> {
+        let g = Grid::parse("219\n398\n", |c| c.to_digit(10))?;
+        assert_eq!((3, 2), (g.width(), g.height()));
+        assert_eq!("219\n398\n", g.to_string());
+        let wide = Grid::from_rows(vec![vec![1, 20], vec![300, 4]])?;
+        assert_eq!("  1  20\n300   4\n", wide.to_string());
+        let e = Grid::parse("12\n3x\n", |c| c.to_digit(10)).unwrap_err();
+        assert!(
+            e.to_string().starts_with("parse error at line 2, column 2"),
+            "{}",
+            e
+        );
+        assert!(Grid::parse("12\n3\n", |c| c.to_digit(10)).is_err());
+        Ok(())
+    }
+}
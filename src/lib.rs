@@ -1,7 +1,11 @@
 extern crate anyhow;
 extern crate nom;
 
+pub mod answers;
+pub mod bench;
 pub mod day1;
+pub mod day1_plot;
+pub mod day1_profile;
 pub mod day2;
 pub mod day3;
 pub mod day4;
@@ -9,13 +13,35 @@ pub mod day5;
 pub mod day6;
 pub mod day6_2;
 pub mod day7;
+pub mod error;
+pub mod fetch;
+pub mod generate;
+pub mod geometry;
 pub mod get_input;
+pub mod grid;
+pub mod parallel;
+pub mod parsers;
+pub mod report;
+pub mod scaffold;
+pub mod solver;
+pub mod stream;
+#[cfg(test)]
+mod test_util;
+pub mod watch;
 
-pub use day1::*;
-pub use day2::*;
-pub use day3::*;
-pub use day4::*;
-pub use day5::*;
-pub use day6::*;
-pub use day6_2::day6_2;
-pub use day7::*;
+pub use error::Error;
+pub use solver::{Answer, Puzzle, Registry, Solver};
+
+/// Every implemented puzzle, ordered by day and part.
+pub fn registry() -> Registry {
+    let mut r = Registry::new();
+    day1::register(&mut r);
+    day2::register(&mut r);
+    day3::register(&mut r);
+    day4::register(&mut r);
+    day5::register(&mut r);
+    day6::register(&mut r);
+    day6_2::register(&mut r);
+    day7::register(&mut r);
+    r
+}
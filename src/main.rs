@@ -1,8 +1,637 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand};
 
-use advent2021::*;
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-fn main() -> Result<()> {
-    println!("day7 2: {:?}", day7_2()?);
+use advent2021::answers::{self, Manifest, Verdict};
+use advent2021::bench::bench;
+use advent2021::day1::{self, Gaps, SonarReadings};
+use advent2021::day1_plot::DepthPlot;
+use advent2021::day1_profile::DepthProfile;
+use advent2021::generate::{self, Options};
+use advent2021::get_input::{InputConfig, InputProvider, InputSource};
+use advent2021::parallel::{run_parallel, Job};
+use advent2021::report::{Format, Outcome, Reporter};
+use advent2021::scaffold;
+use advent2021::solver::Timings;
+use advent2021::watch::{self, Results, Watcher};
+use advent2021::{registry, Registry, Solver};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2021 solutions")]
+struct Cli {
+    #[command(flatten)]
+    inputs: InputArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Directory to look for puzzle inputs in [env: ADVENT_INPUT_DIR]
+    #[arg(long, global = true)]
+    input_dir: Option<PathBuf>,
+    /// Use the named input set under the input directory [env: ADVENT_PROFILE]
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Puzzle year used to find inputs
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    /// Directory fetched inputs are cached in [env: ADVENT_CACHE_DIR]
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    /// Where to fetch missing inputs from: an http:// url or a fixture
+    /// directory [env: ADVENT_FETCH]
+    #[arg(long, global = true)]
+    fetch: Option<String>,
+}
+
+impl InputArgs {
+    fn config(self) -> Result<InputConfig> {
+        let flags = InputConfig {
+            input_dir: self.input_dir,
+            profile: self.profile,
+            year: self.year,
+            cache_dir: self.cache_dir,
+            fetch: self.fetch,
+            session: None,
+        };
+        Ok(InputConfig::from_env()?.overlay(flags))
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one or more puzzles and print their answers
+    Run(RunArgs),
+    /// Run every puzzle and compare the answers against the answers manifest
+    Verify(VerifyArgs),
+    /// Run puzzles repeatedly and report min/median/max timings
+    Bench(BenchArgs),
+    /// Generate the module, example fixture and fuzz target for a new day
+    NewDay(NewDayArgs),
+    /// Re-run a day on its example and real inputs whenever its source or
+    /// inputs change
+    Watch(WatchArgs),
+    /// Write a random input for a day
+    Generate(GenerateArgs),
+    /// Report runs, drops, rolling statistics and anomalies in the day 1
+    /// sonar depths
+    Sonar(SonarArgs),
+    /// Plot the day 1 sonar depths in the terminal or as an SVG file
+    Plot(PlotArgs),
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Answers manifest to check against
+    #[arg(long, default_value = answers::ANSWERS_FILE)]
+    manifest: PathBuf,
+}
+
+#[derive(Args)]
+struct Selection {
+    /// Day to run
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Part to run; both parts are run if omitted
+    #[arg(short, long, requires = "day", value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Run every implemented puzzle
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+}
+
+impl Selection {
+    fn selected(&self, registry: &Registry) -> Result<Vec<&'static dyn Solver>> {
+        let puzzles: Vec<_> = registry
+            .all()
+            .filter(|s| {
+                self.all || (Some(s.day()) == self.day && self.part.is_none_or(|p| p == s.part()))
+            })
+            .collect();
+        if puzzles.is_empty() {
+            Err(anyhow!(
+                "no puzzle implemented for day {}",
+                self.day.unwrap_or(0)
+            ))
+        } else {
+            Ok(puzzles)
+        }
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[command(flatten)]
+    selection: Selection,
+    /// Read the input from this file instead, or from stdin if `-`
+    #[arg(short, long, requires = "day")]
+    input: Option<String>,
+    /// Report parse and solve times for each puzzle
+    #[arg(short, long)]
+    time: bool,
+    /// How to print results
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+    /// Number of puzzles to run at once [default: number of CPUs]
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: Option<u32>,
+    /// Give up on a puzzle after this many seconds
+    #[arg(long, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+    /// Read the input a line at a time rather than all at once, for days
+    /// that can
+    #[arg(long, requires = "day", conflicts_with_all = ["jobs", "timeout"])]
+    stream: bool,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Day to generate an input for
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Seed for the generator; a random one is picked and printed if omitted
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Number of depths, commands, readings, boards, vent lines, fish or crabs
+    #[arg(short = 'n', long)]
+    count: Option<usize>,
+    /// Largest bingo number, vent coordinate or crab position
+    #[arg(long)]
+    max: Option<u32>,
+    /// Bits in each diagnostic reading
+    #[arg(long)]
+    bits: Option<u32>,
+    /// Rows and columns in each bingo board
+    #[arg(long)]
+    board_size: Option<usize>,
+    /// Only generate horizontal and vertical vent lines
+    #[arg(long)]
+    no_diagonals: bool,
+    /// Write the input to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SonarArgs {
+    /// Readings in each rolling window
+    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    window: u32,
+    /// Flag readings more than this many standard deviations from the mean
+    /// of the window before them
+    #[arg(long, default_value_t = 3.0)]
+    sigmas: f64,
+    /// Read the depths from this file instead, or from stdin if `-`
+    #[arg(short, long)]
+    input: Option<String>,
+    /// Also print the statistics for every window
+    #[arg(long)]
+    rolling: bool,
+    /// Print the whole report as JSON
+    #[arg(long, conflicts_with = "rolling")]
+    json: bool,
+    /// What to do with blank or malformed lines; skipped and interpolated
+    /// lines are listed on stderr
+    #[arg(long, value_enum, default_value_t)]
+    gaps: Gaps,
+}
+
+#[derive(Args)]
+struct PlotArgs {
+    /// Also draw the sums of this many readings, scaled to the depths
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    window: Option<u32>,
+    /// Read the depths from this file instead, or from stdin if `-`
+    #[arg(short, long)]
+    input: Option<String>,
+    /// What to do with blank or malformed lines; skipped and interpolated
+    /// lines are listed on stderr
+    #[arg(long, value_enum, default_value_t)]
+    gaps: Gaps,
+    /// Write an SVG image here instead of drawing in the terminal
+    #[arg(long)]
+    svg: Option<PathBuf>,
+    /// Chart width, in characters or for --svg in pixels
+    #[arg(long)]
+    width: Option<u32>,
+    /// Chart height, in lines or for --svg in pixels
+    #[arg(long)]
+    height: Option<u32>,
+}
+
+fn parse_seconds(s: &str) -> std::result::Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    Duration::try_from_secs_f64(secs).map_err(|e| format!("{}", e))
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[command(flatten)]
+    selection: Selection,
+    /// Number of times to run each puzzle
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+}
+
+#[derive(Args)]
+struct NewDayArgs {
+    /// Day to generate
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Puzzle title; defaults to "Day N"
+    #[arg(long)]
+    title: Option<String>,
+    /// Root of the crate to add the day to
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// Day to watch
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Part to run; both parts are run if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// How often to check for changes, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    interval: u64,
+    /// Root of the crate to watch and rebuild
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+}
+
+fn run(args: RunArgs, provider: &dyn InputProvider) -> Result<()> {
+    let puzzles = args.selection.selected(&registry())?;
+    let source = args.input.as_deref().map(InputSource::from);
+    let mut inputs = HashMap::new();
+    let jobs = puzzles
+        .into_iter()
+        .map(|solver| {
+            let input = inputs.entry(solver.day()).or_insert_with(|| {
+                match &source {
+                    Some(s) => s.read(),
+                    None => provider.input(solver.day()),
+                }
+                .map(Arc::from)
+                .map_err(|e| e.to_string())
+            });
+            Job {
+                solver,
+                input: input.clone(),
+            }
+        })
+        .collect();
+    let workers = match args.jobs {
+        Some(n) => n as usize,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+
+    let mut reporter = Reporter::new(args.format, args.time, io::stdout(), io::stderr());
+    reporter.begin()?;
+    let mut failed = 0;
+    let mut written = Ok(());
+    run_parallel(jobs, workers, args.timeout, |outcome: Outcome| {
+        if outcome.result.is_err() {
+            failed += 1;
+        }
+        if written.is_ok() {
+            written = reporter.report(&outcome);
+        }
+    });
+    written?;
+    if failed > 0 {
+        Err(anyhow!("{} puzzle(s) failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs the selected puzzles one after another, each reading its input as
+/// a stream.
+fn run_streaming(args: RunArgs, config: &InputConfig) -> Result<()> {
+    let puzzles = args.selection.selected(&registry())?;
+    let source = match (args.input.as_deref(), args.selection.day) {
+        (Some(s), _) => InputSource::from(s),
+        (None, Some(day)) => InputSource::File(config.input_path(day)?),
+        (None, None) => return Err(anyhow!("--stream needs a day")),
+    };
+    if source == InputSource::Stdin && puzzles.len() > 1 {
+        return Err(anyhow!(
+            "stdin can only be streamed to one part; pass --part"
+        ));
+    }
+
+    let mut reporter = Reporter::new(args.format, args.time, io::stdout(), io::stderr());
+    reporter.begin()?;
+    let mut failed = 0;
+    for solver in puzzles {
+        let start = Instant::now();
+        let answer = source
+            .open()
+            .map_err(|e| e.to_string())
+            .and_then(|mut input| match solver.solve_reader(&mut input) {
+                Some(answer) => answer.map_err(|e| e.to_string()),
+                None => Err("no streaming solution for this puzzle".to_string()),
+            });
+        let timings = Timings {
+            parse: Duration::ZERO,
+            solve: start.elapsed(),
+        };
+        let outcome = Outcome {
+            solver,
+            result: answer.map(|a| (a, timings)),
+        };
+        if outcome.result.is_err() {
+            failed += 1;
+        }
+        reporter.report(&outcome)?;
+    }
+    if failed > 0 {
+        Err(anyhow!("{} puzzle(s) failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn verify(args: VerifyArgs, provider: &dyn InputProvider, profile: &str) -> Result<()> {
+    let manifest = Manifest::load(&args.manifest)?;
+    let checks = answers::verify(&registry(), provider, &manifest, profile);
+    println!(
+        "{:>3} {:>4}  {:<24} {:>16} {:>16}  result",
+        "day", "part", "title", "expected", "actual"
+    );
+    for c in checks.iter() {
+        let actual = c.actual.as_ref().map_or(String::new(), |a| a.to_string());
+        let (expected, result) = match &c.verdict {
+            Verdict::Pass => (actual.clone(), "PASS".to_string()),
+            Verdict::Fail { expected } => (expected.to_string(), "FAIL".to_string()),
+            Verdict::Unknown => ("?".to_string(), "UNKNOWN".to_string()),
+            Verdict::Error(e) => (String::new(), format!("ERROR: {}", e)),
+        };
+        println!(
+            "{:>3} {:>4}  {:<24} {:>16} {:>16}  {}",
+            c.day, c.part, c.title, expected, actual, result
+        );
+    }
+    let failed = checks.iter().filter(|c| c.is_failure()).count();
+    if failed > 0 {
+        Err(anyhow!("{} of {} puzzle(s) failed", failed, checks.len()))
+    } else {
+        Ok(())
+    }
+}
+
+fn bench_cmd(args: BenchArgs, provider: &dyn InputProvider) -> Result<()> {
+    let puzzles = args.selection.selected(&registry())?;
+    let mut inputs = HashMap::new();
+    println!(
+        "{:>3} {:>4}  {:<24} {:>5} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "day", "part", "title", "runs", "min", "median", "max", "parse", "solve"
+    );
+    let mut failed = 0;
+    for solver in puzzles {
+        let input = inputs
+            .entry(solver.day())
+            .or_insert_with(|| provider.input(solver.day()));
+        let stats = match input {
+            Ok(s) => bench(solver, s, args.runs as usize).map_err(Into::into),
+            Err(e) => Err(anyhow!("{}", e)),
+        };
+        match stats {
+            Ok(s) => println!(
+                "{:>3} {:>4}  {:<24} {:>5} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+                solver.day(),
+                solver.part(),
+                solver.title(),
+                s.runs,
+                s.min,
+                s.median,
+                s.max,
+                s.median_parse,
+                s.median_solve
+            ),
+            Err(e) => {
+                failed += 1;
+                eprintln!(
+                    "{:>3} {:>4}  {:<24} error: {:#}",
+                    solver.day(),
+                    solver.part(),
+                    solver.title(),
+                    e
+                );
+            }
+        }
+    }
+    if failed > 0 {
+        Err(anyhow!("{} puzzle(s) failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn new_day(args: NewDayArgs) -> Result<()> {
+    let day = args.day;
+    let title = args.title.unwrap_or_else(|| format!("Day {}", day));
+    for path in scaffold::new_day(&args.root, day, &title)? {
+        println!("wrote {}", path.display());
+    }
+    println!(
+        "paste the example from the puzzle text into {}/day{}.txt",
+        scaffold::FIXTURE_DIR,
+        day
+    );
+    Ok(())
+}
+
+fn generate_cmd(args: GenerateArgs) -> Result<()> {
+    let seed = args.seed.unwrap_or_else(|| {
+        let seed = rand::random();
+        eprintln!("seed {}", seed);
+        seed
+    });
+    let options = Options {
+        count: args.count,
+        max: args.max,
+        bits: args.bits,
+        board_size: args.board_size,
+        no_diagonals: args.no_diagonals,
+    };
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    generate::generate(args.day, &options, seed, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Reads the day 1 depths from `input`, or the configured input if none is
+/// given, listing any skipped or interpolated lines on stderr.
+fn read_sonar(
+    input: Option<&str>,
+    gaps: Gaps,
+    provider: &dyn InputProvider,
+) -> Result<SonarReadings> {
+    let input = match input {
+        Some(s) => InputSource::from(s).read()?,
+        None => provider.input(1)?,
+    };
+    let readings = day1::parse_sonar(&input, gaps)?;
+    let action = match gaps {
+        Gaps::Interpolate => "interpolated",
+        _ => "skipped",
+    };
+    for bad in readings.bad.iter() {
+        eprintln!("line {}: {} {:?}", bad.line, action, bad.text);
+    }
+    Ok(readings)
+}
+
+fn sonar(args: SonarArgs, provider: &dyn InputProvider) -> Result<()> {
+    let readings = read_sonar(args.input.as_deref(), args.gaps, provider)?;
+    let profile = DepthProfile::new(&readings.depths, args.window as usize, args.sigmas);
+    if args.json {
+        println!("{}", serde_json::to_string(&profile)?);
+        return Ok(());
+    }
+    print!("{}", profile);
+    if args.rolling {
+        println!(
+            "{:>8} {:>10} {:>10} {:>8} {:>8}",
+            "from", "mean", "sd", "min", "max"
+        );
+        for w in profile.rolling.iter() {
+            println!(
+                "{:>8} {:>10.1} {:>10.2} {:>8} {:>8}",
+                w.start + 1,
+                w.mean,
+                w.std_dev,
+                w.min,
+                w.max
+            );
+        }
+    }
     Ok(())
 }
+
+fn plot(args: PlotArgs, provider: &dyn InputProvider) -> Result<()> {
+    let readings = read_sonar(args.input.as_deref(), args.gaps, provider)?;
+    let plot = DepthPlot::new(readings.depths, args.window.map(|w| w as usize));
+    match &args.svg {
+        Some(path) => fs::write(
+            path,
+            plot.svg(args.width.unwrap_or(1200), args.height.unwrap_or(600)),
+        )?,
+        None => print!(
+            "{}",
+            plot.ascii(
+                args.width.unwrap_or(100) as usize,
+                args.height.unwrap_or(20) as usize
+            )
+        ),
+    }
+    Ok(())
+}
+
+/// Runs the day on each input through a freshly built binary, so edits to
+/// the solver are picked up. Returns `None` if the build failed.
+fn watch_run(args: &WatchArgs, inputs: &[(&str, PathBuf)]) -> Result<Option<Results>> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut results = Results::new();
+    for (label, path) in inputs.iter().filter(|(_, p)| p.is_file()) {
+        let mut cmd = process::Command::new(&cargo);
+        cmd.current_dir(&args.root)
+            .args(["run", "--quiet", "--bin", "advent2021", "--", "run"])
+            .arg("--day")
+            .arg(args.day.to_string())
+            .args(["--format", "json", "--input"])
+            .arg(path)
+            .stderr(Stdio::inherit());
+        if let Some(p) = args.part {
+            cmd.arg("--part").arg(p.to_string());
+        }
+        let output = cmd.output()?;
+        if output.stdout.is_empty() && !output.status.success() {
+            return Ok(None);
+        }
+        watch::read_rows(
+            &mut results,
+            label,
+            &String::from_utf8_lossy(&output.stdout),
+        )?;
+    }
+    Ok(Some(results))
+}
+
+fn watch_cmd(args: WatchArgs, config: &InputConfig) -> Result<()> {
+    let root = args.root.canonicalize()?;
+    let example = format!("{}/day{}.txt", scaffold::FIXTURE_DIR, args.day);
+    let mut inputs = vec![("example", root.join(example))];
+    match config.input_path(args.day) {
+        Ok(p) => inputs.push(("input", p.canonicalize()?)),
+        Err(e) => eprintln!("not running on the real input: {}", e),
+    }
+    let mut paths = watch::source_files(&root, args.day)?;
+    paths.extend(inputs.iter().map(|(_, p)| p.clone()));
+    let mut watcher = Watcher::new(paths);
+    let relative = |p: &Path| p.strip_prefix(&root).unwrap_or(p).display().to_string();
+    let shown: Vec<String> = watcher.paths().iter().map(|p| relative(p)).collect();
+    println!("watching {}", shown.join(", "));
+
+    let mut previous = None;
+    loop {
+        match watch_run(&args, &inputs)? {
+            Some(results) => {
+                for line in watch::describe(previous.as_ref(), &results) {
+                    println!("{}", line);
+                }
+                previous = Some(results);
+            }
+            None => println!("build failed; waiting for changes"),
+        }
+        let changed = loop {
+            thread::sleep(Duration::from_millis(args.interval));
+            let changed: Vec<String> = watcher.changed().into_iter().map(relative).collect();
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        println!("\n{} changed", changed.join(", "));
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = cli.inputs.config()?;
+    let provider = config.provider()?;
+    match cli.command {
+        Command::Run(args) if args.stream => run_streaming(args, &config),
+        Command::Run(args) => run(args, provider.as_ref()),
+        Command::Bench(args) => bench_cmd(args, provider.as_ref()),
+        Command::NewDay(args) => new_day(args),
+        Command::Watch(args) => watch_cmd(args, &config),
+        Command::Generate(args) => generate_cmd(args),
+        Command::Sonar(args) => sonar(args, provider.as_ref()),
+        Command::Plot(args) => plot(args, provider.as_ref()),
+        Command::Verify(args) => verify(
+            args,
+            provider.as_ref(),
+            config
+                .profile
+                .as_deref()
+                .unwrap_or(answers::DEFAULT_PROFILE),
+        ),
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::report::Outcome;
+use crate::solver::{Answer, Solver, Timings};
+
+/// A puzzle to run and its input, or why the input could not be read.
+pub struct Job {
+    pub solver: &'static dyn Solver,
+    pub input: Result<Arc<str>, String>,
+}
+
+type JobResult = Result<(Answer, Timings), String>;
+
+enum Event {
+    /// A job started, with the flag that retires its worker.
+    Started(usize, Instant, Arc<AtomicBool>),
+    Done(usize, JobResult),
+}
+
+fn solve(job: &Job) -> JobResult {
+    let input = job.input.as_ref().map_err(Clone::clone)?;
+    panic::catch_unwind(AssertUnwindSafe(|| job.solver.solve_timed(input)))
+        .map_err(|_| "solver panicked".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Starts a thread taking jobs from `next` until there are none left or it
+/// is retired, which happens once a job it is running times out.
+fn spawn_worker(jobs: Arc<Vec<Job>>, next: Arc<AtomicUsize>, events: Sender<Event>) {
+    let retired = Arc::new(AtomicBool::new(false));
+    thread::spawn(move || loop {
+        if retired.load(Ordering::SeqCst) {
+            return;
+        }
+        let ix = next.fetch_add(1, Ordering::SeqCst);
+        let job = match jobs.get(ix) {
+            Some(job) => job,
+            None => return,
+        };
+        let started = Event::Started(ix, Instant::now(), retired.clone());
+        if events.send(started).is_err() {
+            return;
+        }
+        if events.send(Event::Done(ix, solve(job))).is_err() {
+            return;
+        }
+    });
+}
+
+/// Runs `jobs` on up to `workers` threads, passing each outcome to `report`
+/// in the order the jobs were given.
+///
+/// A job still running after `timeout` is reported as timed out and a new
+/// worker takes its place. Threads can't be cancelled, so the timed out job
+/// keeps running in the background until it finishes or the process exits,
+/// but its worker takes no more jobs.
+pub fn run_parallel(
+    jobs: Vec<Job>,
+    workers: usize,
+    timeout: Option<Duration>,
+    mut report: impl FnMut(Outcome),
+) {
+    let solvers: Vec<&'static dyn Solver> = jobs.iter().map(|j| j.solver).collect();
+    let jobs = Arc::new(jobs);
+    let next = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = mpsc::channel();
+    for _ in 0..workers.clamp(1, solvers.len().max(1)) {
+        spawn_worker(jobs.clone(), next.clone(), tx.clone());
+    }
+
+    let mut deadlines: BTreeMap<usize, (Instant, Arc<AtomicBool>)> = BTreeMap::new();
+    let mut results: Vec<Option<JobResult>> = solvers.iter().map(|_| None).collect();
+    let mut reported = 0;
+    while reported < solvers.len() {
+        let event = match deadlines.values().map(|(d, _)| d).min() {
+            Some(d) => rx.recv_timeout(d.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok(Event::Started(ix, at, retired)) => {
+                if let Some(t) = timeout {
+                    deadlines.insert(ix, (at + t, retired));
+                }
+            }
+            Ok(Event::Done(ix, result)) => {
+                // Jobs that already timed out have no deadline left.
+                if timeout.is_none() || deadlines.remove(&ix).is_some() {
+                    results[ix] = Some(result);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                let expired: Vec<usize> = deadlines
+                    .iter()
+                    .filter(|(_, (d, _))| *d <= now)
+                    .map(|(ix, _)| *ix)
+                    .collect();
+                for ix in expired {
+                    if let Some((_, retired)) = deadlines.remove(&ix) {
+                        retired.store(true, Ordering::SeqCst);
+                    }
+                    let t = timeout.unwrap_or_default();
+                    results[ix] = Some(Err(format!("timed out after {:.2?}", t)));
+                    spawn_worker(jobs.clone(), next.clone(), tx.clone());
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        while let Some(result) = results.get_mut(reported).and_then(Option::take) {
+            report(Outcome {
+                solver: solvers[reported],
+                result,
+            });
+            reported += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Puzzle;
+
+    fn nap(ms: u64) -> crate::error::Result<Answer> {
+        thread::sleep(Duration::from_millis(ms));
+        Ok(ms.into())
+    }
+
+    static SLOW: Puzzle<u64> = Puzzle::new(1, 1, "Slow", |s| Ok(s.len() as u64), nap);
+    static FAST: Puzzle<u64> = Puzzle::new(1, 2, "Fast", |_| Ok(0), nap);
+    static PANICS: Puzzle<()> = Puzzle::new(2, 1, "Panics", |_| Ok(()), |_| panic!("boom"));
+
+    fn job(solver: &'static dyn Solver, input: &str) -> Job {
+        Job {
+            solver,
+            input: Ok(input.into()),
+        }
+    }
+
+    fn run(jobs: Vec<Job>, workers: usize, timeout: Option<Duration>) -> Vec<(u8, u8, String)> {
+        let mut seen = Vec::new();
+        run_parallel(jobs, workers, timeout, |o| {
+            let shown = match o.result {
+                Ok((a, _)) => a.to_string(),
+                Err(e) => e,
+            };
+            seen.push((o.solver.day(), o.solver.part(), shown));
+        });
+        seen
+    }
+
+    #[test]
+    fn test_reports_in_job_order() {
+        let jobs = vec![
+            job(&SLOW, &"x".repeat(60)),
+            job(&FAST, ""),
+            Job {
+                solver: &FAST,
+                input: Err("no input".to_string()),
+            },
+        ];
+        assert_eq!(
+            vec![
+                (1, 1, "60".to_string()),
+                (1, 2, "0".to_string()),
+                (1, 2, "no input".to_string())
+            ],
+            run(jobs, 4, None)
+        );
+    }
+
+    static RUNNING: AtomicUsize = AtomicUsize::new(0);
+    static MOST_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    fn counted_nap(ms: u64) -> crate::error::Result<Answer> {
+        let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        MOST_RUNNING.fetch_max(running, Ordering::SeqCst);
+        let answer = nap(ms);
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+        answer
+    }
+
+    static COUNTED: Puzzle<u64> = Puzzle::new(3, 1, "Counted", |s| Ok(s.len() as u64), counted_nap);
+
+    #[test]
+    fn test_timed_out_workers_retire() {
+        let mut jobs = vec![job(&SLOW, &"x".repeat(300))];
+        jobs.extend((0..20).map(|_| job(&COUNTED, &"x".repeat(30))));
+        let seen = run(jobs, 1, Some(Duration::from_millis(100)));
+        assert!(seen[0].2.starts_with("timed out after"), "{:?}", seen);
+        assert!(seen[1..].iter().all(|s| s.2 == "30"), "{:?}", seen);
+        assert_eq!(1, MOST_RUNNING.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_timeout_and_panic_are_reported() {
+        let jobs = vec![
+            job(&SLOW, &"x".repeat(5000)),
+            job(&PANICS, ""),
+            job(&FAST, ""),
+        ];
+        let start = Instant::now();
+        let seen = run(jobs, 1, Some(Duration::from_millis(100)));
+        assert!(start.elapsed() < Duration::from_secs(4));
+        assert!(seen[0].2.starts_with("timed out after"), "{:?}", seen);
+        assert_eq!((2, 1, "solver panicked".to_string()), seen[1]);
+        assert_eq!((1, 2, "0".to_string()), seen[2]);
+    }
+}
@@ -0,0 +1,118 @@
+use nom::{
+    character::complete::{char, digit1, line_ending, one_of, space0, space1},
+    combinator::{map_res, opt, recognize},
+    multi::{many0_count, many1_count, separated_list1},
+    sequence::{delimited, pair, terminated},
+    IResult,
+};
+
+use std::str::FromStr;
+
+use crate::grid::Grid;
+
+/// An integer with an optional sign. Negative numbers fail to parse as
+/// unsigned types.
+pub fn integer<T: FromStr>(s: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(s)
+}
+
+/// An integer without a sign.
+pub fn unsigned<T: FromStr>(s: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(s)
+}
+
+/// Integers separated by commas on one line, such as `3,4,3,1,2`.
+pub fn comma_list<T: FromStr>(s: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(char(','), integer)(s)
+}
+
+/// Like [`comma_list`], but the numbers may not have a sign, even when `T`
+/// is a signed type.
+pub fn unsigned_comma_list<T: FromStr>(s: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(char(','), unsigned)(s)
+}
+
+/// One or more `item`s, one per line, stopping before any blank line.
+pub fn lines<'a, T>(
+    item: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    separated_list1(line_ending, item)
+}
+
+/// A line break followed by one or more empty lines.
+pub fn blank_line(s: &str) -> IResult<&str, &str> {
+    recognize(pair(line_ending, many1_count(line_ending)))(s)
+}
+
+/// One or more `block`s separated by blank lines.
+pub fn blocks<'a, T>(
+    block: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    separated_list1(blank_line, block)
+}
+
+/// A row of unsigned numbers separated, and possibly led or followed, by
+/// spaces, as in ` 8  2 23  4 24`.
+pub fn aligned_row<T: FromStr>(s: &str) -> IResult<&str, Vec<T>> {
+    delimited(space0, separated_list1(space1, unsigned), space0)(s)
+}
+
+/// Lines of space-aligned numbers, all of the same length.
+pub fn aligned_grid<T: FromStr>(s: &str) -> IResult<&str, Grid<T>> {
+    map_res(lines(aligned_row), Grid::from_rows)(s)
+}
+
+/// `p` followed by any number of line breaks, so input files may end with
+/// a newline or several.
+pub fn with_trailing_newlines<'a, T>(
+    p: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    terminated(p, many0_count(line_ending))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_numbers() -> Result<()> {
+        assert_eq!(("", -12i64), integer("-12")?);
+        assert_eq!(("", 12u8), integer("+12")?);
+        assert!(integer::<u8>("-12").is_err());
+        assert!(integer::<u8>("300").is_err());
+        assert_eq!((",1", 16i32), unsigned("16,1")?);
+        assert!(unsigned::<i32>("-1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_comma_list() -> Result<()> {
+        assert_eq!(("\n", vec![3u8, 4, 3, 1, 2]), comma_list("3,4,3,1,2\n")?);
+        assert_eq!(("", vec![-1i32, 0, 2]), comma_list("-1,0,2")?);
+        assert_eq!((",", vec![1u32]), comma_list("1,")?);
+        assert_eq!(("", vec![1i32, 2]), unsigned_comma_list("1,2")?);
+        assert!(unsigned_comma_list::<i32>("-1,0,2").is_err());
+        assert_eq!((",-2", vec![1i32]), unsigned_comma_list("1,-2")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lines_and_blocks() -> Result<()> {
+        let input = "1\r\n2\r\n\r\n3\n4\n\n\n5\n";
+        let (rest, v) = with_trailing_newlines(blocks(lines(unsigned::<u32>)))(input)?;
+        assert_eq!("", rest);
+        assert_eq!(vec![vec![1, 2], vec![3, 4], vec![5]], v);
+        assert_eq!(("\n\n3", vec![1u8, 2]), lines(unsigned)("1\n2\n\n3")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aligned_grid() -> Result<()> {
+        let (rest, g) = aligned_grid::<u32>(" 1  2 \r\n33  4\n\n5 6")?;
+        assert_eq!("\n\n5 6", rest);
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![33, 4]])?, g);
+        assert!(aligned_grid::<u32>("1 2\n3\n").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use serde::Serialize;
+
+use std::io::{self, Write};
+
+use crate::solver::{Answer, Solver, Timings};
+
+/// How the runner prints each puzzle's result.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// One line per puzzle; errors go to stderr
+    #[default]
+    Plain,
+    /// One JSON object per puzzle
+    Json,
+    /// A Markdown table
+    Markdown,
+}
+
+/// The result of running one puzzle.
+pub struct Outcome {
+    pub solver: &'static dyn Solver,
+    pub result: Result<(Answer, Timings), String>,
+}
+
+#[derive(Serialize)]
+struct JsonTimings {
+    parse_ns: u128,
+    solve_ns: u128,
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    day: u8,
+    part: u8,
+    title: &'a str,
+    answer: Option<&'a Answer>,
+    timings: Option<JsonTimings>,
+    error: Option<&'a str>,
+}
+
+impl<'a> From<&'a Outcome> for JsonRow<'a> {
+    fn from(o: &'a Outcome) -> Self {
+        let (answer, timings, error) = match &o.result {
+            Ok((a, t)) => (
+                Some(a),
+                Some(JsonTimings {
+                    parse_ns: t.parse.as_nanos(),
+                    solve_ns: t.solve.as_nanos(),
+                }),
+                None,
+            ),
+            Err(e) => (None, None, Some(e.as_str())),
+        };
+        Self {
+            day: o.solver.day(),
+            part: o.solver.part(),
+            title: o.solver.title(),
+            answer,
+            timings,
+            error,
+        }
+    }
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Writes outcomes to `out` in the chosen format, with timings if `timed`.
+/// Only plain output writes to `err`.
+pub struct Reporter<O, E> {
+    format: Format,
+    timed: bool,
+    out: O,
+    err: E,
+}
+
+impl<O: Write, E: Write> Reporter<O, E> {
+    pub fn new(format: Format, timed: bool, out: O, err: E) -> Self {
+        Self {
+            format,
+            timed,
+            out,
+            err,
+        }
+    }
+    /// Writes anything that has to come before the first outcome.
+    pub fn begin(&mut self) -> io::Result<()> {
+        if self.format != Format::Markdown {
+            return Ok(());
+        }
+        if self.timed {
+            writeln!(self.out, "| day | part | title | answer | parse | solve |")?;
+            writeln!(self.out, "|----:|-----:|-------|-------:|------:|------:|")
+        } else {
+            writeln!(self.out, "| day | part | title | answer |")?;
+            writeln!(self.out, "|----:|-----:|-------|-------:|")
+        }
+    }
+    pub fn report(&mut self, o: &Outcome) -> io::Result<()> {
+        let s = o.solver;
+        match (self.format, &o.result) {
+            (Format::Plain, Ok((answer, t))) if self.timed => writeln!(
+                self.out,
+                "day{} {} ({}): {}  [parse {:.2?}, solve {:.2?}]",
+                s.day(),
+                s.part(),
+                s.title(),
+                answer,
+                t.parse,
+                t.solve
+            ),
+            (Format::Plain, Ok((answer, _))) => writeln!(
+                self.out,
+                "day{} {} ({}): {}",
+                s.day(),
+                s.part(),
+                s.title(),
+                answer
+            ),
+            (Format::Plain, Err(e)) => writeln!(
+                self.err,
+                "day{} {} ({}): error: {}",
+                s.day(),
+                s.part(),
+                s.title(),
+                e
+            ),
+            (Format::Json, _) => {
+                serde_json::to_writer(&mut self.out, &JsonRow::from(o))?;
+                writeln!(self.out)
+            }
+            (Format::Markdown, result) => {
+                let (answer, parse, solve) = match result {
+                    Ok((a, t)) => (
+                        markdown_cell(&a.to_string()),
+                        format!("{:.2?}", t.parse),
+                        format!("{:.2?}", t.solve),
+                    ),
+                    Err(e) => (
+                        format!("error: {}", markdown_cell(e)),
+                        String::new(),
+                        String::new(),
+                    ),
+                };
+                write!(
+                    self.out,
+                    "| {} | {} | {} | {} |",
+                    s.day(),
+                    s.part(),
+                    markdown_cell(s.title()),
+                    answer
+                )?;
+                if self.timed {
+                    write!(self.out, " {} | {} |", parse, solve)?;
+                }
+                writeln!(self.out)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Puzzle;
+
+    use std::time::Duration;
+
+    static PIPES: Puzzle<()> = Puzzle::new(9, 1, "Pipe | Dream", |_| Ok(()), |_| Ok(0.into()));
+
+    fn outcomes() -> Vec<Outcome> {
+        vec![
+            Outcome {
+                solver: &PIPES,
+                result: Ok((
+                    Answer::Int(26984457539),
+                    Timings {
+                        parse: Duration::from_micros(3),
+                        solve: Duration::from_millis(2),
+                    },
+                )),
+            },
+            Outcome {
+                solver: &PIPES,
+                result: Err("no winner!".to_string()),
+            },
+        ]
+    }
+
+    fn render(format: Format, timed: bool) -> (String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let mut r = Reporter::new(format, timed, &mut out, &mut err);
+        r.begin().unwrap();
+        for o in outcomes().iter() {
+            r.report(o).unwrap();
+        }
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_plain() {
+        let (out, err) = render(Format::Plain, false);
+        assert_eq!("day9 1 (Pipe | Dream): 26984457539\n", out);
+        assert_eq!("day9 1 (Pipe | Dream): error: no winner!\n", err);
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let (out, err) = render(Format::Json, false);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            r#"{"day":9,"part":1,"title":"Pipe | Dream","answer":26984457539,"timings":{"parse_ns":3000,"solve_ns":2000000},"error":null}"#,
+            lines[0]
+        );
+        assert_eq!(
+            r#"{"day":9,"part":1,"title":"Pipe | Dream","answer":null,"timings":null,"error":"no winner!"}"#,
+            lines[1]
+        );
+        assert!(err.is_empty());
+    }
+
+    #[test]
+    fn test_markdown() {
+        let (out, _) = render(Format::Markdown, true);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!("| day | part | title | answer | parse | solve |", lines[0]);
+        assert_eq!(
+            "| 9 | 1 | Pipe \\| Dream | 26984457539 | 3.00µs | 2.00ms |",
+            lines[2]
+        );
+        assert_eq!(
+            "| 9 | 1 | Pipe \\| Dream | error: no winner! |  |  |",
+            lines[3]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{anyhow, Result};
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where example inputs from the puzzle text live, relative to the crate root.
+pub const FIXTURE_DIR: &str = "tests/fixtures/example";
+
+const MODULE_TEMPLATE: &str = r#"use crate::error::{finish, Error, Result};
+use crate::get_input::read_day;
+use crate::parsers::lines;
+use crate::solver::Registry;
+use nom::{character::complete::not_line_ending, combinator::map, IResult};
+
+fn parse_line(s: &str) -> IResult<&str, String> {
+    map(not_line_ending, String::from)(s)
+}
+
+fn parse_lines(s: &str) -> IResult<&str, Vec<String>> {
+    lines(parse_line)(s)
+}
+
+fn read_lines(input: &str) -> Result<Vec<String>> {
+    finish(input, parse_lines(input))
+}
+
+fn part1(_lines: &[String]) -> Result<u64> {
+    Err(Error::invalid("day __DAY__ part 1 is not solved yet"))
+}
+
+fn part2(_lines: &[String]) -> Result<u64> {
+    Err(Error::invalid("day __DAY__ part 2 is not solved yet"))
+}
+
+pub fn solve_part1(input: &str) -> Result<u64> {
+    part1(&read_lines(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u64> {
+    part2(&read_lines(input)?)
+}
+
+pub fn day__DAY___1() -> Result<u64> {
+    solve_part1(&read_day(__DAY__)?)
+}
+
+pub fn day__DAY___2() -> Result<u64> {
+    solve_part2(&read_day(__DAY__)?)
+}
+
+// Uncomment a part and its registration once it is solved: every
+// registered part is checked against the example input.
+//
+// use crate::solver::Puzzle;
+//
+// static PART1: Puzzle<Vec<String>> = Puzzle::new(__DAY__, 1, __TITLE__, read_lines, |l| {
+//     Ok(part1(&l)?.into())
+// });
+// static PART2: Puzzle<Vec<String>> = Puzzle::new(__DAY__, 2, __TITLE__, read_lines, |l| {
+//     Ok(part2(&l)?.into())
+// });
+
+pub fn register(_registry: &mut Registry) {
+    // _registry.register(&PART1);
+    // _registry.register(&PART2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../__FIXTURE__");
+
+    #[test]
+    fn test_parse_example() -> Result<()> {
+        read_lines(EXAMPLE)?;
+        Ok(())
+    }
+}
+"#;
+
+const FUZZ_TEMPLATE: &str = r#"#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use advent2021::day__DAY__;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = day__DAY__::solve_part1(s);
+        let _ = day__DAY__::solve_part2(s);
+    }
+});
+"#;
+
+fn fixture_path(day: u8) -> String {
+    format!("{}/day{}.txt", FIXTURE_DIR, day)
+}
+
+/// Source for a new day module with parser, solver and test stubs. The
+/// stub parts are left unregistered until they are solved.
+pub fn render_module(day: u8, title: &str) -> String {
+    MODULE_TEMPLATE
+        .replace("__TITLE__", &format!("{:?}", title))
+        .replace("__FIXTURE__", &fixture_path(day))
+        .replace("__DAY__", &day.to_string())
+}
+
+fn render_fuzz_target(day: u8) -> String {
+    FUZZ_TEMPLATE.replace("__DAY__", &day.to_string())
+}
+
+/// The day a line such as `pub mod day6_2;` or `    day6_2::register(&mut r);`
+/// belongs to, if it is one that starts with `prefix` and ends with `suffix`.
+fn line_day(line: &str, prefix: &str, suffix: &str) -> Option<u8> {
+    let line = line.trim();
+    if !line.ends_with(suffix) {
+        return None;
+    }
+    let rest = line.strip_prefix(prefix)?.strip_prefix("day")?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+/// Inserts `new` after the last line for a day up to `day`, keeping the
+/// lines matching `prefix` and `suffix` ordered by day.
+fn insert_line(
+    lines: &mut Vec<String>,
+    (prefix, suffix): (&str, &str),
+    day: u8,
+    new: String,
+) -> Result<()> {
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, l)| line_day(l, prefix, suffix).map(|d| (ix, d)))
+        .collect();
+    let ix = match days.iter().rev().find(|(_, d)| *d <= day) {
+        Some((ix, _)) => ix + 1,
+        None => days.first().map(|(ix, _)| *ix).ok_or_else(|| {
+            anyhow!(
+                "no existing `{}day` lines to add day {} next to",
+                prefix,
+                day
+            )
+        })?,
+    };
+    lines.insert(ix, new);
+    Ok(())
+}
+
+/// Adds the module declaration and registration for `day` to the source
+/// of `lib.rs`.
+pub fn add_to_lib(lib: &str, day: u8) -> Result<String> {
+    let module = format!("pub mod day{};", day);
+    if lib.lines().any(|l| l.trim() == module) {
+        return Err(anyhow!("lib.rs already declares day{}", day));
+    }
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+    insert_line(&mut lines, ("pub mod ", ";"), day, module)?;
+    insert_line(
+        &mut lines,
+        ("", "::register(&mut r);"),
+        day,
+        format!("    day{}::register(&mut r);", day),
+    )?;
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+fn add_fuzz_bin(manifest: &str, day: u8) -> String {
+    format!(
+        "{}\n\n[[bin]]\nname = \"day{day}\"\npath = \"fuzz_targets/day{day}.rs\"\ntest = false\ndoc = false\n",
+        manifest.trim_end(),
+        day = day
+    )
+}
+
+fn create(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut f = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| anyhow!("could not create {}: {}", path.display(), e))?;
+    f.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Generates the module, example fixture and fuzz target for `day` in the
+/// crate at `root` and registers the module, returning the paths written.
+/// Nothing is written if the day already has a module.
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("day must be between 1 and 25, got {}", day));
+    }
+    let lib_path = root.join("src/lib.rs");
+    let module_path = root.join(format!("src/day{}.rs", day));
+    if module_path.exists() {
+        return Err(anyhow!("{} already exists", module_path.display()));
+    }
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|e| anyhow!("could not read {}: {}", lib_path.display(), e))?;
+    let lib = add_to_lib(&lib, day)?;
+
+    let mut written = Vec::new();
+    create(&module_path, &render_module(day, title))?;
+    written.push(module_path);
+    fs::write(&lib_path, lib)?;
+    written.push(lib_path);
+    let fixture = root.join(fixture_path(day));
+    if !fixture.exists() {
+        create(&fixture, "")?;
+        written.push(fixture);
+    }
+    let fuzz_manifest = root.join("fuzz/Cargo.toml");
+    if fuzz_manifest.exists() {
+        let target = root.join(format!("fuzz/fuzz_targets/day{}.rs", day));
+        create(&target, &render_fuzz_target(day))?;
+        written.push(target);
+        let manifest = fs::read_to_string(&fuzz_manifest)?;
+        fs::write(&fuzz_manifest, add_fuzz_bin(&manifest, day))?;
+        written.push(fuzz_manifest);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+
+    const LIB: &str = "pub mod bench;
+pub mod day1;
+pub mod day6;
+pub mod day6_2;
+pub mod day7;
+pub mod error;
+
+pub fn registry() -> Registry {
+    let mut r = Registry::new();
+    day1::register(&mut r);
+    day6::register(&mut r);
+    day6_2::register(&mut r);
+    day7::register(&mut r);
+    r
+}
+";
+
+    #[test]
+    fn test_add_to_lib_keeps_days_ordered() -> Result<()> {
+        let lib = add_to_lib(LIB, 8)?;
+        assert!(lib.contains("pub mod day7;\npub mod day8;\npub mod error;"));
+        assert!(lib.contains("day7::register(&mut r);\n    day8::register(&mut r);\n    r\n"));
+        let lib = add_to_lib(&lib, 2)?;
+        assert!(lib.contains("pub mod day1;\npub mod day2;\npub mod day6;"));
+        assert!(lib.contains("day1::register(&mut r);\n    day2::register(&mut r);"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_to_lib_rejects_existing_day() {
+        assert!(add_to_lib(LIB, 7).is_err());
+    }
+
+    #[test]
+    fn test_render_module() {
+        let m = render_module(12, "Passage \"Pathing\"");
+        assert!(m.contains("pub fn day12_1() -> Result<u64>"));
+        assert!(m.contains("Puzzle::new(12, 2, \"Passage \\\"Pathing\\\"\", read_lines"));
+        assert!(m.contains("include_str!(\"../tests/fixtures/example/day12.txt\")"));
+        assert!(!m.contains("__"));
+    }
+
+    #[test]
+    fn test_render_module_imports_are_used() {
+        let m = render_module(12, "Passage Pathing");
+        let code: Vec<&str> = m
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.starts_with("//"))
+            .collect();
+        let (body, tests) = code.split_at(code.iter().position(|&l| l == "mod tests {").unwrap());
+        let test_uses: Vec<&&str> = tests.iter().filter(|l| l.starts_with("use ")).collect();
+        assert_eq!(vec![&"use super::*;"], test_uses);
+        let (uses, rest): (Vec<&str>, Vec<&str>) = body.iter().partition(|l| l.starts_with("use "));
+        let rest = rest.join("\n");
+        for name in uses.iter().flat_map(|u| {
+            let names = u.trim_end_matches(';').rsplit("::").next().unwrap();
+            names.trim_matches(|c| c == '{' || c == '}').split(", ")
+        }) {
+            let used = rest
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|w| w == name);
+            assert!(used, "template imports {} without using it", name);
+        }
+    }
+
+    #[test]
+    fn test_new_day_writes_files() -> Result<()> {
+        let root = scratch_dir("scaffold");
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/lib.rs"), LIB)?;
+        let written = new_day(&root, 9, "Smoke Basin")?;
+        assert_eq!(3, written.len());
+        assert!(root.join("src/day9.rs").exists());
+        assert!(root.join("tests/fixtures/example/day9.txt").exists());
+        assert!(fs::read_to_string(root.join("src/lib.rs"))?.contains("pub mod day9;"));
+        assert!(new_day(&root, 9, "Smoke Basin").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::Result;
+use serde::{Serialize, Serializer};
+
+use std::fmt;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+/// A puzzle answer, normalised so that solvers with different return types
+/// can be compared and printed uniformly.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Self::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Int(n) => serializer.serialize_i128(*n),
+            Self::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+pub trait Solver: Send + Sync {
+    fn day(&self) -> u8;
+    fn part(&self) -> u8;
+    fn title(&self) -> &'static str;
+    /// Solves the puzzle, timing parsing and solving separately.
+    fn solve_timed(&self, input: &str) -> Result<(Answer, Timings)>;
+    fn solve(&self, input: &str) -> Result<Answer> {
+        self.solve_timed(input).map(|(a, _)| a)
+    }
+    /// Solves the puzzle reading the input a line at a time, or `None` if
+    /// it has no streaming solution.
+    fn solve_reader(&self, _input: &mut dyn BufRead) -> Option<Result<Answer>> {
+        None
+    }
+}
+
+/// A `Solver` made of a parser producing `T` and a function solving from
+/// it, which is what every day uses.
+pub struct Puzzle<T> {
+    day: u8,
+    part: u8,
+    title: &'static str,
+    parse: fn(&str) -> Result<T>,
+    solve: fn(T) -> Result<Answer>,
+    stream: Option<fn(&mut dyn BufRead) -> Result<Answer>>,
+}
+
+impl<T> Puzzle<T> {
+    pub const fn new(
+        day: u8,
+        part: u8,
+        title: &'static str,
+        parse: fn(&str) -> Result<T>,
+        solve: fn(T) -> Result<Answer>,
+    ) -> Self {
+        Self {
+            day,
+            part,
+            title,
+            parse,
+            solve,
+            stream: None,
+        }
+    }
+    /// Adds a solution that reads the input as a stream; see
+    /// [`Solver::solve_reader`].
+    pub const fn with_stream(self, stream: fn(&mut dyn BufRead) -> Result<Answer>) -> Self {
+        Self {
+            stream: Some(stream),
+            ..self
+        }
+    }
+}
+
+impl<T> Solver for Puzzle<T> {
+    fn day(&self) -> u8 {
+        self.day
+    }
+    fn part(&self) -> u8 {
+        self.part
+    }
+    fn title(&self) -> &'static str {
+        self.title
+    }
+    fn solve_timed(&self, input: &str) -> Result<(Answer, Timings)> {
+        let start = Instant::now();
+        let parsed = (self.parse)(input)?;
+        let parse = start.elapsed();
+        let start = Instant::now();
+        let answer = (self.solve)(parsed)?;
+        let solve = start.elapsed();
+        Ok((answer, Timings { parse, solve }))
+    }
+    fn solve_reader(&self, input: &mut dyn BufRead) -> Option<Result<Answer>> {
+        self.stream.map(|stream| stream(input))
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    solvers: Vec<&'static dyn Solver>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn register(&mut self, solver: &'static dyn Solver) {
+        self.solvers.push(solver);
+        self.solvers.sort_by_key(|s| (s.day(), s.part()));
+    }
+    pub fn all(&self) -> impl Iterator<Item = &'static dyn Solver> + '_ {
+        self.solvers.iter().copied()
+    }
+    pub fn day(&self, day: u8) -> impl Iterator<Item = &'static dyn Solver> + '_ {
+        self.all().filter(move |s| s.day() == day)
+    }
+    pub fn get(&self, day: u8, part: u8) -> Option<&'static dyn Solver> {
+        self.day(day).find(|s| s.part() == part)
+    }
+    pub fn len(&self) -> usize {
+        self.solvers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.solvers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    static DOUBLE: Puzzle<u64> = Puzzle::new(
+        2,
+        1,
+        "Double",
+        |s| {
+            s.trim()
+                .parse()
+                .map_err(|e| Error::parse(s, s, format!("{}", e)))
+        },
+        |n| Ok((n * 2).into()),
+    );
+    static ECHO: Puzzle<String> =
+        Puzzle::new(1, 2, "Echo", |s| Ok(s.to_string()), |s| Ok(s.into()));
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from("EFJKZLBL").to_string(), "EFJKZLBL");
+    }
+
+    #[test]
+    fn test_registry_order_and_lookup() -> Result<()> {
+        let mut r = Registry::new();
+        r.register(&DOUBLE);
+        r.register(&ECHO);
+        let order: Vec<(u8, u8)> = r.all().map(|s| (s.day(), s.part())).collect();
+        assert_eq!(order, vec![(1, 2), (2, 1)]);
+        let s = r.get(2, 1).expect("day 2 part 1 registered");
+        assert_eq!(s.solve("21\n")?, Answer::Int(42));
+        assert!(r.get(2, 2).is_none());
+        Ok(())
+    }
+}
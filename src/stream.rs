@@ -0,0 +1,111 @@
+use nom::IResult;
+
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+use crate::error::{finish, Result};
+
+/// Reads input a line at a time, so days with single-pass solutions can
+/// handle inputs larger than memory. `\n` and `\r\n` line endings are both
+/// stripped.
+pub struct LineReader<R> {
+    reader: R,
+    buf: String,
+    line: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            line: 0,
+        }
+    }
+    /// The number of the line last returned, counting from 1.
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+    /// The next line, or `None` at the end of the input.
+    pub fn next_line(&mut self) -> Result<Option<&str>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        let line = self.buf.strip_suffix('\n').unwrap_or(&self.buf);
+        Ok(Some(line.strip_suffix('\r').unwrap_or(line)))
+    }
+    /// Parses every non-blank line with `parse`, which must consume the
+    /// whole line.
+    pub fn parse_lines<T>(self, parse: fn(&str) -> IResult<&str, T>) -> ParsedLines<R, T> {
+        ParsedLines {
+            lines: self,
+            parse,
+            _item: PhantomData,
+        }
+    }
+}
+
+/// Parses every non-blank line of `input` the way [`LineReader::parse_lines`]
+/// does, so a day's in-memory and streaming solvers accept the same inputs.
+pub fn parse_str_lines<T>(input: &str, parse: fn(&str) -> IResult<&str, T>) -> Result<Vec<T>> {
+    LineReader::new(input.as_bytes())
+        .parse_lines(parse)
+        .collect()
+}
+
+/// Iterator over the parsed lines of a [`LineReader`]; errors name the line
+/// they were found on.
+pub struct ParsedLines<R, T> {
+    lines: LineReader<R>,
+    parse: fn(&str) -> IResult<&str, T>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T> Iterator for ParsedLines<R, T> {
+    type Item = Result<T>;
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            let line = match self.lines.next_line() {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => line,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            let parsed = finish(line, (self.parse)(line));
+            return Some(parsed.map_err(|e| e.on_line(self.lines.line_number())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::unsigned;
+
+    #[test]
+    fn test_next_line() -> Result<()> {
+        let mut r = LineReader::new("a\r\nb\n\nc".as_bytes());
+        let mut lines = Vec::new();
+        while let Some(l) = r.next_line()? {
+            lines.push(l.to_string());
+        }
+        assert_eq!(vec!["a", "b", "", "c"], lines);
+        assert_eq!(4, r.line_number());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let r = LineReader::new("1\r\n2\n\n3\n".as_bytes());
+        let v: Result<Vec<u32>> = r.parse_lines(unsigned).collect();
+        assert_eq!(vec![1, 2, 3], v.unwrap());
+        let r = LineReader::new("1\n2\n3x\n".as_bytes());
+        let e = r.parse_lines(unsigned::<u32>).collect::<Result<Vec<_>>>();
+        assert_eq!(
+            "parse error at line 3, column 2: unparsed input: \"3x\"",
+            e.unwrap_err().to_string()
+        );
+    }
+}
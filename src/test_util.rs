@@ -0,0 +1,28 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp dir for one test, removed when
+/// dropped so a failing test doesn't leave it behind.
+pub struct ScratchDir(PathBuf);
+
+impl Deref for ScratchDir {
+    type Target = Path;
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// An empty `ScratchDir` named after `name` and the test process.
+pub fn scratch_dir(name: &str) -> ScratchDir {
+    let d = std::env::temp_dir().join(format!("advent2021-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&d);
+    fs::create_dir_all(&d).unwrap();
+    ScratchDir(d)
+}
@@ -0,0 +1,185 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Polls a set of files for changes to their modification times. Files
+/// that do not exist yet are watched for being created.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let stamps = paths.iter().map(|p| modified(p)).collect();
+        Self { paths, stamps }
+    }
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+    /// The paths that changed since the last call.
+    pub fn changed(&mut self) -> Vec<&Path> {
+        let mut changed = Vec::new();
+        for (path, stamp) in self.paths.iter().zip(self.stamps.iter_mut()) {
+            let now = modified(path);
+            if now != *stamp {
+                *stamp = now;
+                changed.push(path.as_path());
+            }
+        }
+        changed
+    }
+}
+
+/// The source files for `day` under `<root>/src`: `dayN.rs` and any
+/// `dayN_*.rs` split out of it.
+pub fn source_files(root: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    let module = format!("day{}", day);
+    let mut files: Vec<PathBuf> = fs::read_dir(root.join("src"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension().is_some_and(|e| e == "rs")
+                && p.file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|s| s == module || s.starts_with(&format!("{}_", module)))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Each part's answer, or the error it failed with, keyed by the name of
+/// the input it was run on and the part.
+pub type Results = BTreeMap<(String, u8), String>;
+
+#[derive(Deserialize)]
+struct Row {
+    part: u8,
+    answer: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+/// Reads the JSON lines printed by `run --format json` into `results`
+/// under `label`.
+pub fn read_rows(results: &mut Results, label: &str, json_lines: &str) -> Result<()> {
+    for line in json_lines.lines().filter(|l| !l.trim().is_empty()) {
+        let row: Row = serde_json::from_str(line)
+            .map_err(|e| anyhow!("unexpected runner output {:?}: {}", line, e))?;
+        let shown = match (row.answer, row.error) {
+            (_, Some(e)) => format!("error: {}", e),
+            (Some(serde_json::Value::String(s)), None) => s,
+            (Some(a), None) => a.to_string(),
+            (None, None) => "no answer".to_string(),
+        };
+        results.insert((label.to_string(), row.part), shown);
+    }
+    Ok(())
+}
+
+/// One line per result, noting how it differs from `previous`.
+pub fn describe(previous: Option<&Results>, current: &Results) -> Vec<String> {
+    let mut lines: Vec<String> = current
+        .iter()
+        .map(|(key, now)| {
+            let (label, part) = key;
+            let change = match previous.map(|p| p.get(key)) {
+                None => String::new(),
+                Some(None) => "  (new)".to_string(),
+                Some(Some(was)) if was == now => "  (unchanged)".to_string(),
+                Some(Some(was)) => format!("  (was {})", was),
+            };
+            format!("{:<8} part {}: {}{}", label, part, now, change)
+        })
+        .collect();
+    if let Some(previous) = previous {
+        for (label, part) in previous.keys().filter(|k| !current.contains_key(*k)) {
+            lines.push(format!("{:<8} part {}: (no longer run)", label, part));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_util::scratch_dir;
+    use std::time::Duration;
+
+    #[test]
+    fn test_watcher_sees_changes_and_creation() -> Result<()> {
+        let d = scratch_dir("watch");
+        fs::create_dir_all(d.join("src"))?;
+        let src = d.join("src").join("day7.rs");
+        let input = d.join("day7.txt");
+        fs::write(&src, "fn main() {}")?;
+        let mut w = Watcher::new(vec![src.clone(), input.clone()]);
+        assert!(w.changed().is_empty());
+        let f = fs::File::options().write(true).open(&src)?;
+        f.set_modified(SystemTime::now() + Duration::from_secs(5))?;
+        fs::write(&input, "1,2,3")?;
+        assert_eq!(vec![src.as_path(), input.as_path()], w.changed());
+        assert!(w.changed().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_files() -> Result<()> {
+        let d = scratch_dir("watch-sources");
+        fs::create_dir_all(d.join("src"))?;
+        for f in ["day6.rs", "day6_2.rs", "day16.rs", "day6.txt"] {
+            fs::write(d.join("src").join(f), "")?;
+        }
+        let files = source_files(&d, 6)?;
+        assert_eq!(
+            vec![
+                d.join("src").join("day6.rs"),
+                d.join("src").join("day6_2.rs")
+            ],
+            files
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_diffs_against_previous() -> Result<()> {
+        let mut before = Results::new();
+        read_rows(
+            &mut before,
+            "example",
+            "{\"day\":7,\"part\":1,\"answer\":37,\"error\":null}\n\
+             {\"day\":7,\"part\":2,\"answer\":167,\"error\":null}\n",
+        )?;
+        let mut after = Results::new();
+        read_rows(
+            &mut after,
+            "example",
+            "{\"day\":7,\"part\":1,\"answer\":37,\"error\":null}\n\
+             {\"day\":7,\"part\":2,\"answer\":null,\"error\":\"no winner!\"}\n",
+        )?;
+        read_rows(
+            &mut after,
+            "input",
+            "{\"day\":7,\"part\":1,\"answer\":\"ABC\",\"error\":null}\n",
+        )?;
+        assert_eq!(
+            vec![
+                "example  part 1: 37  (unchanged)",
+                "example  part 2: error: no winner!  (was 167)",
+                "input    part 1: ABC  (new)",
+            ],
+            describe(Some(&before), &after)
+        );
+        assert_eq!("example  part 1: 37", describe(None, &before)[0]);
+        Ok(())
+    }
+}
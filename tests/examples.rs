@@ -0,0 +1,136 @@
+//! Checks every day against the worked example from its puzzle text. The
+//! examples live in `tests/fixtures/example/dayN.txt`, so they can also be
+//! run with `--input-dir tests/fixtures --profile example`.
+
+use anyhow::Result;
+
+use std::path::PathBuf;
+
+use advent2021::get_input::InputResolver;
+use advent2021::{day1, day2, day3, day4, day5, day6, day7, registry, Answer, Solver};
+
+/// The answer to each part's example, as given in the puzzle text.
+const ANSWERS: &[(u8, u8, i128)] = &[
+    (1, 1, 7),
+    (1, 2, 5),
+    (2, 1, 150),
+    (2, 2, 900),
+    (3, 1, 198),
+    (3, 2, 230),
+    (4, 1, 4512),
+    (4, 2, 1924),
+    (5, 1, 5),
+    (5, 2, 12),
+    (6, 1, 5934),
+    (6, 2, 26984457539),
+    (7, 1, 37),
+    (7, 2, 168),
+];
+
+/// Registered parts with a known example answer, so days that are still
+/// being worked on are left out.
+fn with_known_answers() -> Vec<(&'static dyn Solver, Answer)> {
+    registry()
+        .all()
+        .filter_map(|s| {
+            ANSWERS
+                .iter()
+                .find(|(day, part, _)| (*day, *part) == (s.day(), s.part()))
+                .map(|(_, _, a)| (s, Answer::Int(*a)))
+        })
+        .collect()
+}
+
+fn example(day: u8) -> Result<String> {
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    Ok(InputResolver::new(fixtures)
+        .with_profile("example")
+        .read(day)?)
+}
+
+#[test]
+fn day1_example() -> Result<()> {
+    let input = example(1)?;
+    assert_eq!(7, day1::solve_part1(&input)?);
+    assert_eq!(5, day1::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day2_example() -> Result<()> {
+    let input = example(2)?;
+    assert_eq!(150, day2::solve_part1(&input)?);
+    assert_eq!(900, day2::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day3_example() -> Result<()> {
+    let input = example(3)?;
+    assert_eq!(198, day3::solve_part1(&input)?);
+    assert_eq!(230, day3::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day4_example() -> Result<()> {
+    let input = example(4)?;
+    assert_eq!(4512, day4::solve_part1(&input)?);
+    assert_eq!(1924, day4::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day5_example() -> Result<()> {
+    let input = example(5)?;
+    assert_eq!(5, day5::solve_part1(&input)?);
+    assert_eq!(12, day5::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day6_example() -> Result<()> {
+    let input = example(6)?;
+    assert_eq!(5934, day6::solve_part1(&input)?);
+    assert_eq!(26984457539, day6::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn day7_example() -> Result<()> {
+    let input = example(7)?;
+    assert_eq!(37, day7::solve_part1(&input)?);
+    assert_eq!(168, day7::solve_part2(&input)?);
+    Ok(())
+}
+
+#[test]
+fn every_registered_day_has_an_example() {
+    let registry = registry();
+    let mut days: Vec<u8> = registry.all().map(|s| s.day()).collect();
+    days.dedup();
+    for day in days {
+        assert!(example(day).is_ok(), "no example fixture for day {}", day);
+    }
+}
+
+#[test]
+fn examples_accept_crlf_and_trailing_blank_lines() -> Result<()> {
+    for (solver, expected) in with_known_answers() {
+        let input = example(solver.day())?;
+        assert_eq!(expected, solver.solve(&input)?, "day {}", solver.day());
+        let crlf = format!("{}\n\n", input.trim_end()).replace('\n', "\r\n");
+        assert_eq!(expected, solver.solve(&crlf)?, "day {}", solver.day());
+    }
+    Ok(())
+}
+
+#[test]
+fn examples_reject_trailing_content() -> Result<()> {
+    for (solver, _) in with_known_answers() {
+        let input = format!("{}\nx\n", example(solver.day())?.trim_end());
+        let e = solver.solve(&input);
+        assert!(e.is_err(), "day {} accepted {:?}", solver.day(), e);
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Solves generated inputs for every day, checking the generators produce
+//! inputs the solvers accept and that the streaming solutions agree.
+
+use anyhow::Result;
+
+use advent2021::generate::{generate, supports, Options};
+use advent2021::{registry, Solver};
+
+/// Registered parts for days that have a generator.
+fn generated_days() -> Vec<&'static dyn Solver> {
+    registry().all().filter(|s| supports(s.day())).collect()
+}
+
+fn input(day: u8, options: &Options, seed: u64) -> Result<String> {
+    let mut out = Vec::new();
+    generate(day, options, seed, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn generated_inputs_solve() -> Result<()> {
+    for seed in 0..5 {
+        for solver in generated_days() {
+            let input = input(solver.day(), &Options::default(), seed)?;
+            let answer = solver.solve(&input);
+            assert!(
+                answer.is_ok(),
+                "day {} part {} seed {}: {:?}",
+                solver.day(),
+                solver.part(),
+                seed,
+                answer
+            );
+            if let Some(streamed) = solver.solve_reader(&mut input.as_bytes()) {
+                assert_eq!(answer?, streamed?, "day {} seed {}", solver.day(), seed);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn small_and_wide_inputs_solve() -> Result<()> {
+    let options = Options {
+        count: Some(3),
+        max: Some(8),
+        bits: Some(2),
+        board_size: Some(3),
+        no_diagonals: false,
+    };
+    let wide = Options {
+        count: Some(50),
+        bits: Some(16),
+        board_size: Some(10),
+        ..Default::default()
+    };
+    for solver in generated_days() {
+        for o in [&options, &wide].iter() {
+            let answer = solver.solve(&input(solver.day(), o, 3)?);
+            assert!(answer.is_ok(), "day {} {:?}: {:?}", solver.day(), o, answer);
+        }
+    }
+    Ok(())
+}
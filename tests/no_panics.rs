@@ -0,0 +1,35 @@
+//! Every solver should reject malformed input with an error rather than
+//! panicking. The fuzz targets under `fuzz/` explore this more widely; these
+//! are the cases that used to panic.
+
+use advent2021::registry;
+
+const HOSTILE: &[&str] = &[
+    "",
+    "\n",
+    "sideways 3\n",
+    "forward 99999999999\n",
+    "up 1\n",
+    "forward 4294967295\nforward 1\n",
+    "down 65536\nforward 65536\n",
+    "1111111111111111111111111111111111111111\n",
+    "1\n0\n",
+    "1,2\n\n 1 2\n 3\n 4 5 6\n 7\n 8\n",
+    "1\n\n",
+    "1,1 -> 99999999999,1\n",
+    "4294967295,5 -> 4294967295,3\n0,1 -> 1,0\n",
+    "3,4,9\n",
+    "3,4,300\n",
+    "2147483647,0,2147483647\n",
+    "18446744073709551615\n18446744073709551615\n18446744073709551615\n1\n",
+];
+
+#[test]
+fn hostile_inputs_do_not_panic() {
+    let registry = registry();
+    for solver in registry.all() {
+        for input in HOSTILE {
+            let _ = solver.solve(input);
+        }
+    }
+}